@@ -3,8 +3,11 @@
 use std::fmt::Debug;
 
 use kproc_macros_examples::default_impl;
+use kproc_macros_examples::derive_associated_types;
 use kproc_macros_examples::derive_fn;
 use kproc_macros_examples::derive_impl;
+use kproc_macros_examples::derive_impl_docs;
+use kproc_macros_examples::derive_receivers;
 use kproc_macros_examples::EnumParser;
 use kproc_macros_examples::RustBuilder;
 
@@ -19,6 +22,52 @@ pub struct Foo {
     self_ref: u32,
 }
 
+#[derive(RustBuilder)]
+#[allow(dead_code)]
+#[doc = "struct with more than one attribute"]
+pub struct MultiAttrs {
+    #[build]
+    #[allow(dead_code)]
+    value: u32,
+    #[build(skip)]
+    #[build(rename = "other")]
+    #[allow(dead_code)]
+    other: u32,
+}
+
+#[derive(RustBuilder)]
+#[allow(dead_code)]
+#[rustfmt::skip]
+pub(crate) struct VisibilityFields {
+    pub a: u32,
+    pub(crate) b: u32,
+    pub(self) d: u32,
+    pub(in crate) e: u32,
+    f: u32,
+}
+
+#[derive(RustBuilder)]
+#[allow(dead_code)]
+pub struct TupleVisibility(pub (u32, u8), pub(crate) u8);
+
+#[derive(RustBuilder)]
+#[allow(dead_code)]
+#[build(?Send)]
+#[build(!Unpin)]
+#[build(fields(a.b = 1), x => y, skip, rename = "b")]
+pub struct ForeignAttrs {
+    #[build(a + b)]
+    a: u8,
+}
+
+#[derive(RustBuilder)]
+#[allow(dead_code)]
+pub struct KeywordLikeFields {
+    publish: u32,
+    fn_name: bool,
+    r#type: String,
+}
+
 #[derive(RustBuilder)]
 pub struct Boo {
     #[allow(dead_code)]
@@ -48,6 +97,34 @@ pub struct BooLifetimeDyn<'a> {
     gen: Vec<&'a dyn GenTrait>,
 }
 
+#[derive(RustBuilder)]
+pub struct WithBoundsAndDefault<'a, T: Clone + Debug = String, const N: usize = 2> {
+    pub value: T,
+    #[allow(dead_code)]
+    pub values: &'a [u8; N],
+}
+
+#[derive(RustBuilder)]
+pub struct WithPathBound<T: std::fmt::Debug + Clone + Into<String>> {
+    pub value: T,
+}
+
+#[derive(RustBuilder)]
+pub struct LifetimeBounds<'a, 'b: 'a, T: Clone + 'b> {
+    pub value: T,
+    #[allow(dead_code)]
+    pub short: &'a T,
+    #[allow(dead_code)]
+    pub long: &'b T,
+}
+
+#[derive(RustBuilder)]
+pub struct WithPaths {
+    pub map: std::collections::HashMap<String, u32>,
+    #[allow(dead_code)]
+    pub option: ::core::option::Option<crate::Meters>,
+}
+
 #[derive(RustBuilder)]
 pub struct BooComplex {
     pub gen: Vec<Foo>,
@@ -58,8 +135,12 @@ pub struct BooComplex {
 }
 
 /// The macros take into count also the comments.
+///
+///     indented line
 #[derive(RustBuilder)]
 pub struct BooComplexCommit {
+    /// The generated values,
+    /// on more lines.
     pub gen: Vec<Foo>,
     #[allow(dead_code)]
     attr: String,
@@ -67,6 +148,33 @@ pub struct BooComplexCommit {
     self_ref: u32,
 }
 
+#[derive(RustBuilder)]
+pub struct Meters(pub f64);
+
+#[derive(RustBuilder)]
+pub struct Pair(#[allow(dead_code)] String, pub u32);
+
+#[derive(RustBuilder)]
+pub struct Marker;
+
+#[derive(RustBuilder)]
+pub struct WithWhere<T>
+where
+    T: Clone,
+{
+    attr: T,
+}
+
+#[derive(RustBuilder)]
+pub struct TupleWithWhere<T>(pub T)
+where
+    T: Clone + Debug;
+
+#[derive(RustBuilder)]
+pub struct UnitWithWhere
+where
+    u32: Clone;
+
 #[derive(EnumParser)]
 enum Message {
     Quit,
@@ -89,6 +197,53 @@ enum MessageWithAttr {
     ChangeColor(i32, i32, i32),
 }
 
+#[derive(EnumParser)]
+enum MessageWithWhere
+where
+    u32: Clone,
+{
+    Quit,
+}
+
+#[derive(EnumParser)]
+#[allow(dead_code)]
+enum Tree<T: Clone + Debug, const N: usize = 2>
+where
+    T: Default,
+{
+    Leaf(T),
+    Node {
+        left: Box<Tree<T, N>>,
+        values: Vec<T>,
+        children: [Option<Box<Tree<T, N>>>; N],
+    },
+    Empty,
+}
+
+#[derive(EnumParser)]
+#[allow(dead_code)]
+enum Code {
+    Ok = 1,
+    Shifted = 1 << 2,
+    Last,
+}
+
+#[derive(EnumParser)]
+#[allow(dead_code)]
+enum Command {
+    #[cli(exit)]
+    #[rustfmt::skip]
+    Quit,
+    #[cli(go)]
+    #[doc = "move the cursor"]
+    /// Move the cursor
+    ///   to the position.
+    #[cli(walk, help = "move to \"x\"", short, level = -1_0i32)]
+    Move { y: i32, x: i32, z: i32 },
+    #[cli(help = r#"write"#, hidden = true)]
+    Write(String, u8),
+}
+
 struct ForImplDerive {}
 
 /// this is a impl doc
@@ -102,6 +257,169 @@ impl ForImplDerive {
         todo!()
     }
 }
+struct ForImplWhere {}
+
+#[derive_impl]
+impl ForImplWhere
+where
+    u32: Clone,
+{
+    fn alibaba(&self) -> Result<(), ()> {
+        todo!()
+    }
+}
+
+trait GenericTrait<T> {
+    fn first(&self) -> T;
+}
+
+#[derive_impl]
+impl<T: Clone> GenericTrait<T> for Vec<T> {
+    fn first(&self) -> T {
+        self[0].clone()
+    }
+}
+
+#[derive_impl]
+impl GenTrait for ForImplWhere {}
+
+#[derive_impl]
+unsafe impl Send for ForImplDerive {}
+
+trait WithAssociatedItems {
+    type Item: Clone + Debug;
+    const SIZE: usize;
+    const DEFAULT_SIZE: usize = 3;
+
+    fn new() -> Self;
+    fn item(&self) -> Self::Item;
+}
+
+trait WithGenericAssociatedType {
+    type Items<T>
+    where
+        T: Clone;
+
+    fn items<T: Clone>(&self, item: T) -> Self::Items<T>;
+}
+
+#[derive_associated_types]
+impl WithGenericAssociatedType for ForImplWhere {
+    type Items<T>
+        = Vec<T>
+    where
+        T: Clone;
+
+    fn items<T: Clone>(&self, item: T) -> Self::Items<T> {
+        vec![item.clone(), item]
+    }
+}
+
+macro_rules! impl_size {
+    ($size:expr) => {
+        const SIZE: usize = $size;
+    };
+}
+
+#[derive_impl]
+impl WithAssociatedItems for ForImplWhere {
+    type Item = u8;
+    impl_size!(2);
+
+    fn new() -> Self {
+        ForImplWhere {}
+    }
+
+    fn item(&self) -> Self::Item {
+        Self::SIZE as u8
+    }
+}
+
+#[default_impl]
+trait DefaultWithAssociatedItems {
+    #![allow(unused_variables)]
+    type Item: Clone;
+    const DEFAULT_SIZE: usize = 3;
+    impl_size!(2);
+}
+
+#[default_impl]
+trait SeqWhere<T>
+where
+    T: Clone,
+{
+    fn len(&self) -> u32;
+}
+
+struct Receivers {
+    value: u32,
+}
+
+#[derive_receivers]
+impl Receivers {
+    fn by_value(self) -> u32 {
+        self.value
+    }
+
+    fn by_mut_value(mut self) -> u32 {
+        self.value += 1;
+        self.value
+    }
+
+    fn by_ref(&self, string: &str) -> usize {
+        string.len()
+    }
+
+    fn by_ref_lifetime<'a>(&'a self) -> u32 {
+        self.value
+    }
+
+    fn by_mut_ref(&mut self, value: u32) {
+        self.value = value;
+    }
+
+    #[allow(clippy::boxed_local)]
+    fn by_box(self: Box<Self>) -> u32 {
+        self.value
+    }
+
+    fn by_ref_with_attrs(#[allow(unused)] &self) -> u32 {
+        self.value
+    }
+
+    fn no_self(string: &str, value: u32) -> usize {
+        string.len() + value as usize
+    }
+}
+
+struct WithImplDocs {}
+
+/// this is an impl docs
+#[derive_impl_docs]
+impl WithImplDocs {
+    //! this is an inner impl docs
+    #![allow(dead_code)]
+
+    fn unused(&self) {}
+}
+
+/// # Safety
+/// this is an unsafe trait
+#[default_impl]
+unsafe trait UnsafeSeq: Clone + Debug {
+    fn len(&self) -> u32;
+}
+
+#[derive_fn]
+pub const unsafe fn const_unsafe_fn() -> u32 {
+    1
+}
+
+#[derive_fn]
+extern "C" fn extern_fn() -> u32 {
+    2
+}
+
 /// this is a trait docs
 #[default_impl]
 trait Seq<T> {
@@ -146,12 +464,148 @@ fn foo_with_bound_with_multiuple_type_double<T: Debug + ToString, R>(
     todo!()
 }
 
+#[derive_fn]
+fn foo_with_where<T, R>(value: T, other: R) -> Option<T>
+where
+    T: Debug + Clone,
+    R: ToString,
+{
+    Some(value)
+}
+
+#[derive_fn]
+fn foo_with_where_hrtb<'a, F>(value: F)
+where
+    'a: 'static,
+    for<'b> F: Copy,
+{
+    unimplemented!()
+}
+
+pub struct Point {
+    x: u32,
+    y: u32,
+}
+
+#[derive_fn]
+#[allow(unused_parens)]
+#[allow(clippy::too_many_arguments)]
+fn sum_patterns(
+    (a, b): (u32, u32),
+    (d,): (u32,),
+    (e): u32,
+    mut c: u32,
+    _: u8,
+    Point { x, y: other }: Point,
+    Meters(meters): Meters,
+    &value: &u32,
+    [first, rest @ ..]: [u32; 3],
+    r#ref: u32,
+) -> u32 {
+    c += 1;
+    a + b + c + d + e + x + other + meters as u32 + value + first + rest.len() as u32 + r#ref
+}
+
+#[derive_fn]
+#[allow(clippy::too_many_arguments)]
+fn ty_kinds(
+    array: [u8; 4],
+    slice: &[u8],
+    tuple: (u8, u16),
+    single: (u8,),
+    unit: (),
+    pointer: *const u8,
+    mut_pointer: *mut u8,
+    callback: fn(u8) -> u8,
+    named_callback: unsafe extern "C" fn(value: u8),
+    iter: impl Clone,
+    dynamic: &dyn Debug,
+    assoc: <Vec<u8> as IntoIterator>::IntoIter,
+) -> (usize, u8) {
+    let _ = (
+        slice,
+        single,
+        unit,
+        pointer,
+        mut_pointer,
+        named_callback,
+        iter,
+        dynamic,
+    );
+    (array.len() + assoc.len(), callback(tuple.0) + tuple.1 as u8)
+}
+
+#[derive_fn]
+fn path_types(
+    map: &std::collections::HashMap<String, u32>,
+    value: ::core::option::Option<crate::Meters>,
+) -> std::vec::Vec<std::string::String> {
+    let _ = value;
+    map.keys().cloned().collect()
+}
+
+pub struct ConstWrapper<const N: usize>;
+
+#[derive_impl]
+impl<const N: usize> ConstWrapper<N> {
+    pub fn size(&self) -> usize {
+        N
+    }
+}
+
+#[default_impl]
+trait WithGenericsDefault<T: Clone = String, const N: usize = 3, const M: i32 = -1> {
+    fn value(&self) -> T;
+}
+
+#[derive_fn]
+fn const_generics<T: Default + Copy, const N: usize>(value: T) -> [T; N] {
+    [value; N]
+}
+
+#[derive_fn]
+fn generic_args<'a>(
+    iter: Box<dyn Iterator<Item = u8>>,
+    other: impl Iterator<Item = u8>,
+    cow: std::borrow::Cow<'a, str>,
+    literal: ConstWrapper<3>,
+    expr: ConstWrapper<{ 1 + 2 }>,
+) -> usize {
+    let _ = (literal, expr);
+    iter.count() + other.count() + cow.len()
+}
+
+#[derive_fn]
+fn trait_bounds<T: Into<String>, U: std::fmt::Debug + ?Sized, F: for<'a> Fn(&'a str) -> bool>(
+    value: T,
+    debug: &U,
+    check: F,
+) -> impl Fn(u8) -> bool
+where
+    T: Clone + 'static,
+{
+    let value: String = value.into();
+    let checked = check(&value) && !format!("{debug:?}").is_empty();
+    move |byte| checked && byte > 0
+}
+
+#[derive_fn]
+fn lifetime_bounds<'a, 'b: 'a, T: Clone + 'b>(value: &'b T) -> &'a T {
+    value
+}
+
+#[derive_fn]
+fn never_returns() -> ! {
+    unimplemented!()
+}
+
 #[derive_fn]
 fn help<C: Debug, F: Debug>(top_level: Option<C>, sucommands: Vec<C>, flags: Vec<F>) {
     unimplemented!()
 }
 
 #[derive_fn]
+#[allow(clippy::ptr_arg)]
 fn help_stress<C: Debug, F: Debug>(top_level: Option<C>, sucommands: &Vec<C>, flags: &[F]) {
     unimplemented!()
 }
@@ -185,6 +639,356 @@ mod tests {
         assert!(obj.gen.is_empty());
     }
 
+    #[test]
+    fn tuple_struct_works() {
+        let obj = crate::Meters(1.5);
+        assert_eq!(obj.get_0(), 1.5);
+
+        let obj = crate::Pair("Alibaba".to_string(), 0);
+        assert_eq!(obj.get_0(), obj.0);
+    }
+
+    #[test]
+    fn where_clause_works() {
+        let obj = crate::WithWhere {
+            attr: "Alibaba".to_string(),
+        };
+        assert_eq!(obj.get_attr(), obj.attr);
+
+        let obj = crate::TupleWithWhere(42);
+        assert_eq!(obj.get_0(), 42);
+
+        assert_eq!(crate::foo_with_where(42, "Alibaba"), Some(42));
+    }
+
+    #[test]
+    fn trait_impl_works() {
+        use crate::GenericTrait;
+
+        let obj = vec![42, 0];
+        assert_eq!(obj.first(), 42);
+    }
+
+    #[test]
+    fn associated_items_works() {
+        use crate::WithAssociatedItems;
+
+        let obj = crate::ForImplWhere::new();
+        assert_eq!(obj.item(), 2);
+        assert_eq!(crate::ForImplWhere::DEFAULT_SIZE, 3);
+
+        use crate::WithGenericAssociatedType;
+        assert_eq!(obj.items(1), vec![1, 1]);
+        assert_eq!(
+            crate::ForImplWhere::ASSOCIATED_TYPES,
+            &["type Items<T> = Vec<T> where T: Clone;"]
+        );
+    }
+
+    #[test]
+    fn receivers_works() {
+        assert_eq!(
+            crate::Receivers::RECEIVERS,
+            &[
+                "self",
+                "mut self",
+                "&self",
+                "&'a self",
+                "&mut self",
+                "self: Box<Self>",
+                "&self",
+                "none",
+            ]
+        );
+        assert_eq!(
+            crate::Receivers::RECEIVERS_DISPLAY,
+            &[
+                "self",
+                "mut self",
+                "&self",
+                "&'a self",
+                "&mut self",
+                "self: Box<Self>",
+                "#[allow(unused)] &self",
+                "",
+            ]
+        );
+        let mut obj = crate::Receivers { value: 1 };
+        obj.by_mut_ref(2);
+        assert_eq!(obj.by_ref("Alibaba"), 7);
+        assert_eq!(crate::Receivers::no_self("Alibaba", 1), 8);
+    }
+
+    #[test]
+    fn fn_patterns_works() {
+        let value = crate::sum_patterns(
+            (1, 1),
+            (1,),
+            1,
+            0,
+            0,
+            crate::Point { x: 1, y: 1 },
+            crate::Meters(1.0),
+            &1,
+            [1, 0, 0],
+            1,
+        );
+        assert_eq!(value, 13);
+    }
+
+    #[test]
+    fn path_types_works() {
+        let mut map = std::collections::HashMap::new();
+        map.insert("Alibaba".to_owned(), 1);
+        let obj = crate::WithPaths { map, option: None };
+        assert_eq!(obj.get_map().len(), 1);
+        assert_eq!(crate::path_types(&obj.map, None), vec!["Alibaba"]);
+    }
+
+    #[test]
+    fn generic_args_works() {
+        let len = crate::generic_args(
+            Box::new(vec![1, 2].into_iter()),
+            vec![3].into_iter(),
+            "Alibaba".into(),
+            crate::ConstWrapper,
+            crate::ConstWrapper,
+        );
+        assert_eq!(len, 10);
+    }
+
+    #[test]
+    fn const_generics_works() {
+        let values = crate::const_generics::<u8, 4>(1);
+        assert_eq!(values, [1; 4]);
+        assert_eq!(crate::ConstWrapper::<5>.size(), 5);
+    }
+
+    #[test]
+    fn split_for_impl_works() {
+        let obj: crate::WithBoundsAndDefault = crate::WithBoundsAndDefault {
+            value: "Alibaba".to_owned(),
+            values: &[1, 2],
+        };
+        assert_eq!(obj.get_value(), "Alibaba");
+    }
+
+    #[test]
+    fn trait_bounds_works() {
+        let check = crate::trait_bounds("Alibaba", "debug", |value| value.starts_with('A'));
+        assert!(check(1));
+        assert!(!check(0));
+    }
+
+    #[test]
+    fn unified_generics_works() {
+        let value = 1;
+        let obj = crate::LifetimeBounds {
+            value,
+            short: crate::lifetime_bounds(&value),
+            long: &value,
+        };
+        assert_eq!(obj.get_value(), 1);
+    }
+
+    #[test]
+    fn enum_variants_works() {
+        assert_eq!(
+            crate::Tree::<u8>::VARIANTS,
+            &[("Leaf", 1, None), ("Node", 3, None), ("Empty", 0, None)]
+        );
+        assert_eq!(
+            crate::Code::VARIANTS,
+            &[
+                ("Ok", 0, Some("1")),
+                ("Shifted", 0, Some("1 << 2")),
+                ("Last", 0, None)
+            ]
+        );
+    }
+
+    #[test]
+    fn enum_fields_order_works() {
+        assert_eq!(
+            crate::Tree::<u8>::FIELDS,
+            &[&["0"][..], &["left", "values", "children"], &[]]
+        );
+        assert_eq!(
+            crate::Command::FIELDS,
+            &[&[][..], &["y", "x", "z"], &["0", "1"]]
+        );
+    }
+
+    #[test]
+    fn repeated_attributes_works() {
+        assert_eq!(
+            crate::Command::CLI,
+            &[&["exit"][..], &["go", "walk"], &["help"]]
+        );
+    }
+
+    #[test]
+    fn attribute_paths_works() {
+        assert_eq!(
+            crate::Command::ATTR_PATHS,
+            &[
+                &["cli", "rustfmt::skip"][..],
+                &["cli", "doc", "doc", "doc", "cli"],
+                &["cli"]
+            ]
+        );
+        assert_eq!(crate::Command::FMT_SKIP, &[true, false, false]);
+    }
+
+    #[test]
+    fn struct_attributes_works() {
+        assert_eq!(crate::MultiAttrs::ATTRS, &["allow", "doc"]);
+        assert_eq!(
+            crate::MultiAttrs::FIELD_ATTRS,
+            &[("value", 2), ("other", 3)]
+        );
+        assert_eq!(crate::Foo::FIELD_ATTRS, &[("attr", 1), ("self_ref", 1)]);
+    }
+
+    #[test]
+    fn visibility_works() {
+        assert_eq!(crate::VisibilityFields::VISIBILITY, "pub(crate)");
+        assert_eq!(
+            crate::VisibilityFields::FIELD_VISIBILITY,
+            &["pub", "pub(crate)", "pub(self)", "pub(in crate)", ""]
+        );
+        assert_eq!(
+            crate::TupleVisibility::FIELD_VISIBILITY,
+            &["pub", "pub(crate)"]
+        );
+        let outcomes = kproc_macros_examples::parse_outcomes!(
+            "pub(crate) struct Foo;",
+            "pub(in crate::a) enum E { A }",
+            "pub(super) trait T { pub(crate) fn foo(&self); }",
+            "impl Foo { pub(crate) fn foo(&self) {} pub(self) const A: u8 = 0; }",
+            "pub(crate) fn f() {}"
+        );
+        for (input, outcome, _) in outcomes {
+            assert_eq!(outcome, "ok", "unexpected outcome for `{input}`");
+        }
+    }
+
+    #[test]
+    fn foreign_attributes_works() {
+        assert_eq!(
+            crate::ForeignAttrs::META_KINDS,
+            &[
+                "list(path)",
+                "list(verbatim)",
+                "list(verbatim)",
+                "list(list(verbatim), verbatim, path, name_value)",
+                "list(verbatim)"
+            ]
+        );
+        assert_eq!(crate::ForeignAttrs::FIELD_ATTRS, &[("a", 1)]);
+        let outcomes = kproc_macros_examples::parse_outcomes!(
+            "#[async_trait(?Send)] impl Foo for Bar { async fn foo(&self) {} }",
+            "#[pin_project(!Unpin)] struct Foo { a: u8 }",
+            "#[tracing(fields(a.b = 1))] fn f() {}",
+            "struct Foo { #[my_attr(a + b)] a: u8 }",
+            "#[async_trait(?Send)] trait T { #[my_attr(x => y)] fn foo(&self); }"
+        );
+        for (input, outcome, _) in outcomes {
+            assert_eq!(outcome, "ok", "unexpected outcome for `{input}`");
+        }
+    }
+
+    #[test]
+    fn unknown_attributes_works() {
+        let outcomes = kproc_macros_examples::parse_outcomes!(
+            "#[unknown(a + b, ?c)] pub struct Foo(#[unknown(=> x)] u8);",
+            "#[unknown(?Sized)] impl Foo { #[unknown(a.b)] const A: u8 = 0; #[unknown(!)] type B = u8; }",
+            "#[unknown(x => y)] pub trait T { #[unknown(1 + 1)] fn foo(#[unknown(&)] a: u8); }",
+            "#[unknown(*)] fn f(#[unknown(a == b)] a: u8) {}",
+            "#[unknown(|x| x)] enum E { #[unknown(..)] A }"
+        );
+        for (input, outcome, _) in outcomes {
+            assert_eq!(outcome, "ok", "unexpected outcome for `{input}`");
+        }
+    }
+
+    #[test]
+    fn keyword_like_identifiers_works() {
+        assert_eq!(
+            crate::KeywordLikeFields::FIELD_ATTRS,
+            &[("publish", 0), ("fn_name", 0), ("r#type", 0)]
+        );
+        let outcomes = kproc_macros_examples::parse_outcomes!(
+            "r#pub struct Foo;",
+            "r#struct Foo;",
+            "pub r#fn f() {}"
+        );
+        for (input, outcome, _) in outcomes {
+            assert_eq!(outcome, "error", "unexpected outcome for `{input}`");
+        }
+    }
+
+    #[test]
+    fn docs_works() {
+        assert_eq!(
+            crate::BooComplexCommit::DOCS,
+            Some("The macros take into count also the comments.\n\n    indented line")
+        );
+        assert_eq!(
+            crate::BooComplexCommit::FIELD_DOCS,
+            &[Some("The generated values,\non more lines."), None, None]
+        );
+        assert_eq!(crate::Foo::DOCS, None);
+        assert_eq!(crate::WithImplDocs::DOCS, Some("this is an impl docs"));
+        assert_eq!(
+            crate::WithImplDocs::INNER_DOCS,
+            Some("this is an inner impl docs")
+        );
+        assert_eq!(
+            crate::Command::DOCS,
+            &[
+                None,
+                Some("move the cursor\n Move the cursor\n   to the position."),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn meta_attributes_works() {
+        assert_eq!(
+            crate::Command::CLI_ARGS,
+            &[
+                (None, false, None, false),
+                (Some("move to \"x\""), true, Some(-10), false),
+                (Some("write"), false, None, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn ty_kinds_works() {
+        extern "C" fn nothing(_: u8) {}
+        let value = 1;
+        let mut other = 2;
+        let (len, sum) = crate::ty_kinds(
+            [0; 4],
+            &[],
+            (1, 2),
+            (1,),
+            (),
+            &value,
+            &mut other,
+            |value| value + 1,
+            nothing,
+            0,
+            &0,
+            vec![1, 2].into_iter(),
+        );
+        assert_eq!(len, 6);
+        assert_eq!(sum, 4);
+    }
+
     #[test]
     fn generics_with_dyn_works() {
         let obj = crate::BooLifetimeDyn {
@@ -194,4 +998,122 @@ mod tests {
         };
         assert!(obj.gen.is_empty());
     }
+
+    #[test]
+    fn malformed_input_works() {
+        let outcomes = kproc_macros_examples::parse_outcomes!(
+            "",
+            "struct",
+            "struct Foo",
+            "struct Foo<",
+            "struct Foo(u32)",
+            "struct Foo { a }",
+            "struct Foo { a: }",
+            "enum",
+            "enum E",
+            "enum E { A B }",
+            "enum E { A = , B }",
+            "enum E { A = }",
+            "enum E { A = 1 B }",
+            "enum E { A = X B(u8) }",
+            "fn f",
+            "fn f()",
+            "fn f(a: u32) ->",
+            "fn f(Point {, }: Point) {}",
+            "fn f(a: [u8;]) {}",
+            "fn f<const N: usize = >() {}",
+            "fn f<const N: usize = -a>() {}",
+            "fn f<T U>() {}",
+            "fn f<'a T>() {}",
+            "impl",
+            "impl Foo",
+            "impl Foo for",
+            "trait T",
+            "trait T { const A }",
+            "trait T { foo }",
+            "#[derive(Debug)]",
+            "#[::] struct Foo;",
+            "#[] struct Foo;",
+            "pub"
+        );
+        // the error points to the offending token, or to the
+        // enclosing group when the input ends too early.
+        let spans = [
+            ";",
+            "struct",
+            "Foo",
+            "<",
+            "(u32)",
+            "{ a }",
+            "{ a: }",
+            "enum",
+            "E",
+            "B",
+            "=",
+            "=",
+            "B",
+            "B",
+            "f",
+            "()",
+            ">",
+            ",",
+            "[u8;]",
+            "=",
+            "=",
+            "U",
+            "T",
+            "impl",
+            "Foo",
+            "for",
+            "T",
+            "{ const A }",
+            "foo",
+            "[derive(Debug)]",
+            "[::]",
+            "[]",
+            "pub",
+        ];
+        assert_eq!(outcomes.len(), spans.len());
+        for ((input, outcome, span), expected) in outcomes.into_iter().zip(spans) {
+            assert_eq!(outcome, "error", "unexpected outcome for `{input}`");
+            assert_eq!(span, expected, "unexpected span for `{input}`");
+        }
+        let outcomes = kproc_macros_examples::parse_outcomes!("struct Foo;", "fn f() {}");
+        for (input, outcome, _) in outcomes {
+            assert_eq!(outcome, "ok", "unexpected outcome for `{input}`");
+        }
+    }
+
+    #[test]
+    fn item_qualifiers_works() {
+        assert_eq!(unsafe { crate::const_unsafe_fn() }, 1);
+        assert_eq!(crate::extern_fn(), 2);
+
+        let outcomes = kproc_macros_examples::parse_outcomes!(
+            "unsafe trait T {}",
+            "trait T: Clone + Sized where Self: Debug {}",
+            "pub const unsafe fn f() {}",
+            "const async unsafe extern fn f() {}",
+            "unsafe const fn f() {}"
+        );
+        let expected = ["ok", "ok", "ok", "ok", "error"];
+        for ((input, outcome, _), expected) in outcomes.into_iter().zip(expected) {
+            assert_eq!(outcome, expected, "unexpected outcome for `{input}`");
+        }
+    }
+
+    #[test]
+    fn parse_backtracking_works() {
+        let outcomes = kproc_macros_examples::parse_outcomes!(
+            "struct Foo;",
+            "#[derive(Debug)] pub struct Foo { a: u32 }",
+            "impl Foo { #[inline] pub fn foo(&self) {} }",
+            "trait T { #[allow(unused)] type A; const B: u32; }",
+            "enum E { A, B(u32) }",
+            "pub fn f() {}"
+        );
+        for (input, outcome, _) in outcomes {
+            assert_eq!(outcome, "ok", "unexpected outcome for `{input}`");
+        }
+    }
 }
@@ -1,26 +1,225 @@
 //! Utils code that it is used to generate other code
 use kproc_parser::proc_macro::TokenStream;
-use kproc_parser::rust::ast_nodes::StructToken;
+use kproc_parser::rust::ast_nodes::{Meta, StructToken};
+use kproc_parser::rust::kenum::{EnumToken, EnumValueKind};
 
 // FIXME: use the filed attribute to generate the get method when the attribute
 // is specified!
 pub fn generate_impl(struct_tok: &StructToken) -> TokenStream {
-    let gen = if let Some(str_gen) = &struct_tok.generics {
-        format!("{}", str_gen)
+    let (impl_gen, ty_gen, where_clause) = struct_tok.split_for_impl();
+    let where_clause = if let Some(where_clause) = where_clause {
+        format!("{where_clause}")
     } else {
         "".to_owned()
     };
+    let attrs = struct_tok
+        .attrs
+        .iter()
+        .map(|attr| format!("{:?}", attr.attr().meta.path_str()))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let field_attrs = struct_tok
+        .fields
+        .iter()
+        .map(|field| format!("(\"{}\", {})", field.identifier, field.attrs.len()))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let meta_kinds = struct_tok
+        .attrs
+        .iter()
+        .chain(
+            struct_tok
+                .fields
+                .iter()
+                .flat_map(|field| field.attrs.iter()),
+        )
+        .map(|attr| format!("{:?}", meta_kind(&attr.attr().meta)))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let docs = struct_tok.docs();
+    let field_docs = struct_tok
+        .fields
+        .iter()
+        .map(|field| format!("{:?}", field.docs()))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let visibility = struct_tok.visibility.to_string();
+    let field_visibility = struct_tok
+        .fields
+        .iter()
+        .map(|field| format!("{:?}", field.visibility.to_string()))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let consts = format!(
+        "#[allow(dead_code)] pub const ATTRS: &'static [&'static str] = &[{attrs}]; \
+         #[allow(dead_code)] pub const FIELD_ATTRS: &'static [(&'static str, usize)] = &[{field_attrs}]; \
+         #[allow(dead_code)] pub const DOCS: Option<&'static str> = {docs:?}; \
+         #[allow(dead_code)] pub const FIELD_DOCS: &'static [Option<&'static str>] = &[{field_docs}]; \
+         #[allow(dead_code)] pub const VISIBILITY: &'static str = {visibility:?}; \
+         #[allow(dead_code)] pub const META_KINDS: &'static [&'static str] = &[{meta_kinds}]; \
+         #[allow(dead_code)] pub const FIELD_VISIBILITY: &'static [&'static str] = &[{field_visibility}];"
+    );
+    // a unit struct do not have any field to generate the
+    // get and set method.
+    if struct_tok.fields.is_empty() {
+        return format!(
+            "impl{} {}{} {} {{ {consts} }}",
+            impl_gen, struct_tok.name, ty_gen, where_clause
+        )
+        .parse()
+        .unwrap();
+    }
     let name_attr = &struct_tok.fields[0].identifier;
     let ty = struct_tok.fields[0].ty.to_string();
     let code = format!(
-        "impl{} {}{} {{ \
+        "impl{} {}{} {} {{ \
+                    {consts} \
                     fn get_{name_attr}(&self) -> {ty} {{ \
                        return self.{name_attr}.clone()\
                     }} \
                        \
                     fn set_{name_attr}(&self, inner: {ty}) {{ }}
                 }}",
-        gen, struct_tok.name, gen,
+        impl_gen, struct_tok.name, ty_gen, where_clause,
+    );
+    code.parse().unwrap()
+}
+
+/// describe the shape of the meta, like `list(path, verbatim)`.
+fn meta_kind(meta: &Meta) -> String {
+    match meta {
+        Meta::Path { .. } => "path".to_owned(),
+        Meta::NameValue { .. } => "name_value".to_owned(),
+        Meta::List { nested, .. } => {
+            let nested = nested.iter().map(meta_kind).collect::<Vec<_>>();
+            format!("list({})", nested.join(", "))
+        }
+        Meta::Lit(_) => "lit".to_owned(),
+        Meta::Verbatim(_) => "verbatim".to_owned(),
+    }
+}
+
+/// generate a `VARIANTS` constant with the name, the number of
+/// fields and the discriminant of each variant of the enum.
+pub fn generate_enum_variants(enum_tok: &EnumToken) -> TokenStream {
+    let (impl_gen, ty_gen, where_clause) = enum_tok.split_for_impl();
+    let where_clause = if let Some(where_clause) = where_clause {
+        format!("{where_clause}")
+    } else {
+        "".to_owned()
+    };
+    let variants = enum_tok
+        .values
+        .iter()
+        .map(|value| {
+            let fields = match &value.kind {
+                EnumValueKind::Named(fields) => fields.len(),
+                EnumValueKind::Anonymus(fields) => fields.len(),
+                EnumValueKind::Simple => 0,
+            };
+            let discriminant = match &value.discriminant {
+                Some(discriminant) => format!("Some({:?})", discriminant.to_string()),
+                None => "None".to_owned(),
+            };
+            format!("(\"{}\", {fields}, {discriminant})", value.identifier)
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+    let fields = enum_tok
+        .values
+        .iter()
+        .map(|value| {
+            let names = match &value.kind {
+                EnumValueKind::Named(fields) => fields
+                    .keys()
+                    .map(|name| format!("\"{name}\""))
+                    .collect::<Vec<String>>(),
+                EnumValueKind::Anonymus(fields) => fields
+                    .iter()
+                    .map(|field| format!("\"{}\"", field.identifier))
+                    .collect::<Vec<String>>(),
+                EnumValueKind::Simple => vec![],
+            };
+            format!("&[{}]", names.join(", "))
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+    let cli = enum_tok
+        .values
+        .iter()
+        .map(|value| {
+            let values = value
+                .attributes
+                .get_all("cli")
+                .filter_map(|attr| attr.attr().value.as_ref())
+                .map(|value| format!("{:?}", value.identifier.to_string()))
+                .collect::<Vec<String>>();
+            format!("&[{}]", values.join(", "))
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+    let cli_args = enum_tok
+        .values
+        .iter()
+        .map(|value| {
+            let attrs = value
+                .attributes
+                .get_all("cli")
+                .map(|attr| attr.attr())
+                .collect::<Vec<_>>();
+            let help = attrs.iter().find_map(|attr| attr.get_str("help"));
+            let short = attrs.iter().any(|attr| attr.has_flag("short"));
+            let level = attrs.iter().find_map(|attr| attr.get_int("level"));
+            let hidden = attrs.iter().find_map(|attr| attr.get_bool("hidden"));
+            format!(
+                "({help:?}, {short}, {level:?}, {})",
+                hidden.unwrap_or_default()
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+    let attr_paths = enum_tok
+        .values
+        .iter()
+        .map(|value| {
+            let paths = value
+                .attributes
+                .iter()
+                .map(|attr| format!("{:?}", attr.attr().meta.path_str()))
+                .collect::<Vec<String>>();
+            format!("&[{}]", paths.join(", "))
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+    let fmt_skip = enum_tok
+        .values
+        .iter()
+        .map(|value| {
+            value
+                .attributes
+                .first("rustfmt::skip")
+                .is_some()
+                .to_string()
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+    let docs = enum_tok
+        .values
+        .iter()
+        .map(|value| format!("{:?}", value.docs()))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let code = format!(
+        "#[allow(dead_code)] impl{} {}{} {} {{ \
+            pub const VARIANTS: &'static [(&'static str, usize, Option<&'static str>)] = &[{variants}]; \
+            pub const FIELDS: &'static [&'static [&'static str]] = &[{fields}]; \
+            pub const CLI: &'static [&'static [&'static str]] = &[{cli}]; \
+            pub const CLI_ARGS: &'static [(Option<&'static str>, bool, Option<i64>, bool)] = &[{cli_args}]; \
+            pub const ATTR_PATHS: &'static [&'static [&'static str]] = &[{attr_paths}]; \
+            pub const FMT_SKIP: &'static [bool] = &[{fmt_skip}]; \
+            pub const DOCS: &'static [Option<&'static str>] = &[{docs}]; \
+        }}",
+        impl_gen, enum_tok.identifier, ty_gen, where_clause,
     );
-    return code.parse().unwrap();
+    code.parse().unwrap()
 }
@@ -1,7 +1,9 @@
 use kproc_parser::kparser::{DummyTracer, KParserTracer};
+use kproc_parser::rust::ast_nodes::{AssociatedItem, Receiver};
 use kproc_parser::rust::kparser::RustParser;
 use kproc_parser::trace;
-use proc_macro::TokenStream;
+use proc_macro::{TokenStream, TokenTree};
+use std::panic::{self, AssertUnwindSafe};
 
 mod gen;
 use crate::gen::*;
@@ -31,7 +33,7 @@ pub fn derive_enum(stream: TokenStream) -> TokenStream {
     let parser = RustParser::with_tracer(&tracer);
     let ast = parser.parse_enum(&stream);
     trace!(tracer, "emum types {:#?}", ast.values);
-    "".parse().unwrap()
+    generate_enum_variants(&ast)
 }
 
 #[proc_macro_attribute]
@@ -41,9 +43,94 @@ pub fn derive_impl(_: TokenStream, input: TokenStream) -> TokenStream {
 
     let ast = parser.parse_impl(&input);
     trace!(tracer, "{}", ast);
+    trace!(tracer, "associated items {:#?}", ast.associated_items);
     ast.to_string().parse().unwrap()
 }
 
+/// generate a `RECEIVERS` constant with the receiver of each
+/// function declared inside the impl block, and a `RECEIVERS_DISPLAY`
+/// constant with the receivers as printed by the parser.
+#[proc_macro_attribute]
+pub fn derive_receivers(_: TokenStream, input: TokenStream) -> TokenStream {
+    let tracer = DummyTracer {};
+    let parser = RustParser::with_tracer(&tracer);
+
+    let ast = parser.parse_impl(&input);
+    let receivers = ast
+        .functions
+        .iter()
+        .map(|func| match func.receiver {
+            Receiver::None => "\"none\"",
+            Receiver::Value { .. } => "\"self\"",
+            Receiver::MutValue { .. } => "\"mut self\"",
+            Receiver::Ref { lifetime: None, .. } => "\"&self\"",
+            Receiver::Ref { .. } => "\"&'a self\"",
+            Receiver::RefMut { .. } => "\"&mut self\"",
+            Receiver::Typed { ref ty, .. } if ty.is_path_to("Box") => "\"self: Box<Self>\"",
+            Receiver::Typed { .. } => "\"self: Ty\"",
+        })
+        .collect::<Vec<&str>>()
+        .join(", ");
+    let displayed = ast
+        .functions
+        .iter()
+        .map(|func| format!("{:?}", func.receiver.to_string()))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let code = format!(
+        "{input} impl {} {{ \
+            pub const RECEIVERS: &'static [&'static str] = &[{receivers}]; \
+            pub const RECEIVERS_DISPLAY: &'static [&'static str] = &[{displayed}]; \
+        }}",
+        ast.self_ty
+    );
+    code.parse().unwrap()
+}
+
+/// generate a `ASSOCIATED_TYPES` constant with the associated
+/// types declared inside the impl block, as printed by the parser.
+#[proc_macro_attribute]
+pub fn derive_associated_types(_: TokenStream, input: TokenStream) -> TokenStream {
+    let tracer = DummyTracer {};
+    let parser = RustParser::with_tracer(&tracer);
+
+    let ast = parser.parse_impl(&input);
+    let types = ast
+        .associated_items
+        .iter()
+        .filter_map(|item| match item {
+            AssociatedItem::AssociatedType(ty) => Some(format!("{:?}", ty.to_string())),
+            _ => None,
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+    let code = format!(
+        "{input} impl {} {{ pub const ASSOCIATED_TYPES: &'static [&'static str] = &[{types}]; }}",
+        ast.self_ty
+    );
+    code.parse().unwrap()
+}
+
+/// generate the `DOCS` and `INNER_DOCS` constants with the
+/// outer and inner doc comments of the impl block.
+#[proc_macro_attribute]
+pub fn derive_impl_docs(_: TokenStream, input: TokenStream) -> TokenStream {
+    let tracer = DummyTracer {};
+    let parser = RustParser::with_tracer(&tracer);
+
+    let ast = parser.parse_impl(&input);
+    let code = format!(
+        "{input} impl {} {{ \
+            pub const DOCS: Option<&'static str> = {:?}; \
+            pub const INNER_DOCS: Option<&'static str> = {:?}; \
+        }}",
+        ast.self_ty,
+        ast.docs(),
+        ast.inner_docs(),
+    );
+    code.parse().unwrap()
+}
+
 #[proc_macro_attribute]
 pub fn default_impl(_: TokenStream, input: TokenStream) -> TokenStream {
     let tracer = DummyTracer {};
@@ -59,5 +146,34 @@ pub fn derive_fn(_: TokenStream, input: TokenStream) -> TokenStream {
     let parser = RustParser::with_tracer(&tracer);
     let ast = parser.parse_fn(&input);
     trace!(tracer, "function AST: {:#?}", ast);
-    input
+    ast.to_string().parse().unwrap()
+}
+
+/// parse each string literal as an item and generate an array
+/// of `(input, outcome, span)` where the outcome is one of `"ok"`,
+/// `"error"` or `"panic"`, and the span is the token of the error.
+#[proc_macro]
+pub fn parse_outcomes(input: TokenStream) -> TokenStream {
+    let tracer = DummyTracer {};
+    let outcomes = input
+        .into_iter()
+        .filter_map(|tok| match tok {
+            TokenTree::Literal(lit) => Some(lit.to_string()),
+            _ => None,
+        })
+        .map(|lit| {
+            let source = lit.trim_matches('"').to_owned();
+            let stream: TokenStream = source.parse().unwrap();
+            let parser = RustParser::with_tracer(&tracer);
+            let (outcome, span) =
+                match panic::catch_unwind(AssertUnwindSafe(|| parser.parse(&stream))) {
+                    Ok(Ok(_)) => ("ok", String::new()),
+                    Ok(Err(err)) => ("error", err.span().to_string()),
+                    Err(_) => ("panic", String::new()),
+                };
+            format!("({lit}, \"{outcome}\", {span:?})")
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+    format!("[{outcomes}]").parse().unwrap()
 }
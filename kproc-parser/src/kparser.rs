@@ -41,7 +41,7 @@ impl KParserError {
     }
 
     pub fn expect(expect_tok: &str, tok: &TokenTree, line: String, file: String) -> Result<()> {
-        if expect_tok != &tok.to_string() {
+        if expect_tok != tok.to_string() {
             let msg = format!("expected `{expect_tok}` but got `{tok}`");
             return Err(KParserError {
                 dig: KDiagnInfo::new(&msg, tok.to_owned(), line, file),
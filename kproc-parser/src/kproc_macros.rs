@@ -1,19 +1,81 @@
 //! core module that implement the basic concept
 //! used inside the parser.
-use std::fmt::Debug;
+use std::cell::OnceCell;
+use std::fmt::{self, Debug};
+use std::rc::Rc;
 
-use crate::proc_macro::{TokenStream, TokenTree};
+use crate::build_error;
+use crate::kparser::{self, KParserError};
+use crate::proc_macro::{Punct, Spacing, TokenStream, TokenTree};
+
+/// Flattened tokens of a `TokenStream` shared between all
+/// the cursors that point inside it.
+///
+/// The content of a group is flattened only the first time
+/// that a cursor descends into it, and then it is reused.
+struct TokenBuffer {
+    tokens: Vec<TokenTree>,
+    /// the buffer of each token that is a `TokenTree::Group`,
+    /// at the same index of the token.
+    groups: Vec<OnceCell<Rc<TokenBuffer>>>,
+}
+
+impl TokenBuffer {
+    fn new(stream: TokenStream) -> Rc<Self> {
+        let tokens = stream.into_iter().collect::<Vec<_>>();
+        let groups = tokens.iter().map(|_| OnceCell::new()).collect();
+        Rc::new(TokenBuffer { tokens, groups })
+    }
+
+    /// return the buffer of the group at the given index, or
+    /// `None` if the token is not a `TokenTree::Group`.
+    fn group(&self, idx: usize) -> Option<Rc<TokenBuffer>> {
+        match &self.tokens[idx] {
+            TokenTree::Group(group) => Some(
+                self.groups[idx]
+                    .get_or_init(|| TokenBuffer::new(group.stream()))
+                    .clone(),
+            ),
+            _ => None,
+        }
+    }
+}
 
 /// Convinient way to manage any kind of tokens stream
 /// by exposing basic function to advance and consume
 /// the stream.
 ///
+/// The stream is a cursor inside a shared buffer, so
+/// cloning it or descending into a group does not copy
+/// the tokens.
+///
 /// Heavenly inspired to `albert_stream` <https://github.com/vincenzopalazzo/albert/tree/main/stream>
-#[derive(Debug)]
+#[derive(Clone)]
 pub struct KTokenStream {
     pos: usize,
-    kstream: Vec<TokenTree>,
+    kstream: Rc<TokenBuffer>,
     size: usize,
+    /// The group that contains the stream, if any, used to
+    /// report an error at the end of the stream.
+    enclosing: Option<TokenTree>,
+}
+
+/// Position inside a `KTokenStream` returned by `checkpoint`
+/// and used to `rewind` the stream after a failed attempt
+/// to parse an alternative.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    pos: usize,
+}
+
+impl Debug for KTokenStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KTokenStream")
+            .field("pos", &self.pos)
+            .field("kstream", &self.kstream.tokens)
+            .field("size", &self.size)
+            .finish()
+    }
 }
 
 impl From<TokenStream> for KTokenStream {
@@ -37,33 +99,79 @@ impl KTokenStream {
     /// create the a new instance from a TokenStream and the
     /// initial position
     pub fn new_with_pos(tokens: &TokenStream, pos: usize) -> Self {
-        let mut kstream = Vec::new();
-        tokens
-            .to_owned()
-            .into_iter()
-            .for_each(|token| kstream.push(token));
+        KTokenStream::with_buffer(TokenBuffer::new(tokens.clone()), pos, None)
+    }
+
+    fn with_buffer(kstream: Rc<TokenBuffer>, pos: usize, enclosing: Option<TokenTree>) -> Self {
         KTokenStream {
             pos,
-            kstream: kstream.to_vec(),
-            size: kstream.len(),
+            size: kstream.tokens.len(),
+            kstream,
+            enclosing,
         }
     }
 
-    /// advance the position and return the previous element
-    /// in position - 1
-    pub fn advance(&mut self) -> TokenTree {
-        self.next();
+    /// create a new instance from the content of the group
+    /// at the current position, by sharing the buffer of the
+    /// group with all the other cursors.
+    fn current_group(&self) -> Option<Self> {
         if self.is_end() {
-            return self.kstream.last().unwrap().to_owned();
+            return None;
         }
-        self.prev().to_owned()
+        let buffer = self.kstream.group(self.pos)?;
+        let group = self.kstream.tokens[self.pos].clone();
+        Some(KTokenStream::with_buffer(buffer, 0, Some(group)))
+    }
+
+    /// return the current token and advance the position.
+    ///
+    /// # Panics
+    ///
+    /// Panics at the end of the stream.
+    #[deprecated(note = "please use `try_advance`, that returns an error at the end of the stream")]
+    pub fn advance(&mut self) -> TokenTree {
+        match self.try_advance() {
+            Ok(tok) => tok,
+            Err(_) => panic!("advance: out of bound"),
+        }
+    }
+
+    /// return the current token and advance the position, or an
+    /// error if the stream is at the end.
+    pub fn try_advance(&mut self) -> kparser::Result<TokenTree> {
+        let tok = self.try_peek()?.clone();
+        self.next();
+        Ok(tok)
+    }
+
+    /// consume the current token only if it satisfies the
+    /// predicate, that it is never the case at the end of
+    /// the stream.
+    pub fn next_if(&mut self, pred: impl FnOnce(&TokenTree) -> bool) -> Option<TokenTree> {
+        let tok = self.try_peek().ok().filter(|tok| pred(tok))?.clone();
+        self.next();
+        Some(tok)
     }
 
     /// perform a search operation inside the stream by a number
     /// of specified steps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the step is out of the stream, please check it
+    /// with `has` or use `try_lookup` to get an error instead.
     pub fn lookup(&self, step: usize) -> &TokenTree {
         assert!(self.size > self.pos + step);
-        &self.kstream[self.pos + step]
+        &self.kstream.tokens[self.pos + step]
+    }
+
+    /// same as `lookup` but return an error if the step
+    /// is out of the stream.
+    pub fn try_lookup(&self, step: usize) -> kparser::Result<&TokenTree> {
+        if !self.has(step) {
+            return Err(self.unexpected_end());
+        }
+        Ok(&self.kstream.tokens[self.pos + step])
     }
 
     /// perform a search operation inside the stream by a number
@@ -77,14 +185,63 @@ impl KTokenStream {
         self.pos += 1;
     }
 
+    /// save the current position of the stream, so the
+    /// parser can try an alternative and come back with
+    /// `rewind` if it fails.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint { pos: self.pos }
+    }
+
+    /// restore the position saved with `checkpoint`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the checkpoint is out of the stream, so the
+    /// checkpoint must be taken from the same stream.
+    pub fn rewind(&mut self, checkpoint: Checkpoint) {
+        assert!(checkpoint.pos <= self.size, "rewind: out of bound");
+        self.pos = checkpoint.pos;
+    }
+
+    /// create a copy of the stream at the current position
+    /// that can be consumed without touching the original one.
+    pub fn fork(&self) -> KTokenStream {
+        self.clone()
+    }
+
     /// take the previous element of the stream from the
     /// current position.
+    ///
+    /// # Panics
+    ///
+    /// Panics at the beginning of the stream.
+    #[deprecated(
+        note = "please use `try_prev`, that returns an error at the beginning of the stream"
+    )]
     pub fn prev(&self) -> &TokenTree {
-        assert!(self.pos < self.size, "prev: out of bound");
-        &self.kstream[self.pos - 1]
+        assert!(self.pos > 0 && self.pos <= self.size, "prev: out of bound");
+        &self.kstream.tokens[self.pos - 1]
+    }
+
+    /// take the previous element of the stream from the
+    /// current position, or an error at the beginning of
+    /// the stream.
+    pub fn try_prev(&self) -> kparser::Result<&TokenTree> {
+        if self.pos == 0 || self.pos > self.size {
+            return Err(build_error!(
+                self.end_span(),
+                "no token before the current position"
+            ));
+        }
+        Ok(&self.kstream.tokens[self.pos - 1])
     }
 
     /// return he token at the current position.
+    ///
+    /// # Panics
+    ///
+    /// Panics at the end of the stream, please check it with
+    /// `is_end` or use `try_peek` to get an error instead.
     pub fn peek(&self) -> &TokenTree {
         assert!(
             self.pos < self.size,
@@ -92,17 +249,97 @@ impl KTokenStream {
             self.pos,
             self.size
         );
-        &self.kstream[self.pos]
+        &self.kstream.tokens[self.pos]
+    }
+
+    /// return the token at the current position, or an error
+    /// if the stream is at the end.
+    pub fn try_peek(&self) -> kparser::Result<&TokenTree> {
+        if self.is_end() {
+            return Err(self.unexpected_end());
+        }
+        Ok(&self.kstream.tokens[self.pos])
+    }
+
+    /// return the token that it is used to report an error
+    /// at the end of the stream, that is the enclosing group
+    /// or the last token of the stream.
+    pub fn end_span(&self) -> TokenTree {
+        self.enclosing
+            .clone()
+            .or_else(|| self.kstream.tokens.last().cloned())
+            .unwrap_or_else(|| TokenTree::Punct(Punct::new(';', Spacing::Alone)))
+    }
+
+    /// build the error for an unexpected end of the stream.
+    pub fn unexpected_end(&self) -> KParserError {
+        build_error!(self.end_span(), "unexpected end of input")
+    }
+
+    /// consume the current token if it is a `TokenTree::Group` and
+    /// return the stream of tokens inside the group.
+    pub fn expect_group(&mut self) -> kparser::Result<KTokenStream> {
+        let tok = self.try_peek()?;
+        let stream = self
+            .current_group()
+            .ok_or_else(|| build_error!(tok.clone(), "expected a group but got `{tok}`"))?;
+        self.next();
+        Ok(stream)
+    }
+
+    /// consume the current token if it is a `TokenTree::Ident`.
+    pub fn expect_ident(&mut self) -> kparser::Result<TokenTree> {
+        match self.try_peek()? {
+            TokenTree::Ident(_) => self.try_advance(),
+            tok => Err(build_error!(
+                tok.clone(),
+                "expected an identifier but got `{tok}`"
+            )),
+        }
+    }
+
+    /// consume the current token if it is the `TokenTree::Punct`
+    /// with the given char.
+    pub fn expect_punct(&mut self, punct: char) -> kparser::Result<TokenTree> {
+        match self.try_peek()? {
+            TokenTree::Punct(tok) if tok.as_char() == punct => self.try_advance(),
+            tok => Err(build_error!(
+                tok.clone(),
+                "expected `{punct}` but got `{tok}`"
+            )),
+        }
+    }
+
+    /// consume the current token if it is the one specified,
+    /// like a keyword.
+    pub fn expect_tok(&mut self, tok: &str) -> kparser::Result<TokenTree> {
+        let next = self.try_peek()?.clone();
+        KParserError::expect(tok, &next, line!().to_string(), file!().to_string())?;
+        self.next();
+        Ok(next)
+    }
+
+    /// return the tokens of the stream as `TokenStream`.
+    pub fn to_stream(&self) -> TokenStream {
+        match &self.enclosing {
+            Some(TokenTree::Group(group)) => group.stream(),
+            _ => TokenStream::from_iter(self.kstream.tokens.iter().cloned()),
+        }
     }
 
     /// return the last token of the stream.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the stream is empty.
     pub fn last(&self) -> &TokenTree {
-        self.kstream.last().unwrap()
+        self.kstream.tokens.last().unwrap()
     }
 
-    /// match the current token with the one specified.
+    /// match the current token with the one specified, that
+    /// is always false at the end of the stream.
     pub fn match_tok(&self, tok: &str) -> bool {
-        self.peek().match_tok(tok)
+        !self.is_end() && self.peek().match_tok(tok)
     }
 
     /// check if it is reach the end of the stream
@@ -111,25 +348,30 @@ impl KTokenStream {
     }
 
     /// unwrap the `TokenTree::Group` and the return the
-    /// token stream that contains. If the current token
-    /// it is not a `TokenTree::Group` the function will panic
+    /// token stream that contains.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current token it is not a `TokenTree::Group`.
+    #[deprecated(note = "please use `expect_group` or `opt_ktoken_stream`, that do not panic")]
     pub fn to_ktoken_stream(&self) -> KTokenStream {
-        match self.peek() {
-            TokenTree::Group(stream) => KTokenStream::new(&stream.stream()),
-            _ => panic!("no stream on token {:?}", self.peek()),
-        }
+        self.current_group()
+            .unwrap_or_else(|| panic!("no stream on token {:?}", self.peek()))
     }
 
     /// unwrap the `TokenTree::Group` and the return the
-    /// token stream that contains. If the current token
-    /// it is not a `TokenTree::Group` the function will panic
+    /// token stream that contains, or `None` if the current
+    /// token it is not a `TokenTree::Group`.
     pub fn opt_ktoken_stream(&self) -> Option<KTokenStream> {
-        match self.peek() {
-            TokenTree::Group(stream) => Some(KTokenStream::new(&stream.stream())),
-            _ => None,
-        }
+        self.current_group()
     }
 
+    /// return the current token if it is a `TokenTree::Group`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current token it is not a `TokenTree::Group`.
+    #[deprecated(note = "please use `expect_group`, that returns an error")]
     pub fn unwrap_group(&self) -> TokenTree {
         match self.peek() {
             TokenTree::Group(_) => self.peek().clone(),
@@ -137,7 +379,13 @@ impl KTokenStream {
         }
     }
 
+    /// return the stream inside the current `TokenTree::Group`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current token it is not a `TokenTree::Group`.
     // FIXME: this should consume the token
+    #[deprecated(note = "please use `expect_group`, that returns an error")]
     pub fn unwrap_group_as_stream(&self) -> TokenStream {
         match self.peek() {
             TokenTree::Group(stream) => stream.stream(),
@@ -147,20 +395,13 @@ impl KTokenStream {
 
     /// check if the current token is a `TokenTree::Group`
     pub fn is_group(&self) -> bool {
-        match self.peek() {
-            TokenTree::Group(_) => true,
-            _ => false,
-        }
+        matches!(self.try_peek(), Ok(TokenTree::Group(_)))
     }
 
     // FIXME: this can be removed?
     pub fn consume_brace(&mut self) {
-        let tok = self.peek();
-        match tok.to_string().as_str() {
-            "{" | "}" => {
-                self.advance();
-            }
-            _ => {}
+        if self.match_tok("{") || self.match_tok("}") {
+            self.next();
         }
     }
 }
@@ -172,18 +413,9 @@ where
     fn match_tok(&self, tok: &str) -> bool {
         self.to_string().as_str() == tok
     }
-
-    fn to_token_stream(&self) -> KTokenStream;
 }
 
-impl MatchTok for TokenTree {
-    fn to_token_stream(&self) -> KTokenStream {
-        match self {
-            TokenTree::Group(stream) => KTokenStream::new(&stream.stream()),
-            _ => panic!("no stream on token {:?}", self),
-        }
-    }
-}
+impl MatchTok for TokenTree {}
 
 #[derive(Clone, Debug)]
 pub struct OrderedTokenTree {
@@ -213,7 +445,7 @@ impl From<TokenTree> for OrderedTokenTree {
 
 impl PartialOrd for OrderedTokenTree {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.idx.partial_cmp(&other.idx)
+        Some(self.cmp(other))
     }
 }
 
@@ -230,3 +462,51 @@ impl PartialEq for OrderedTokenTree {
 }
 
 impl Eq for OrderedTokenTree {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ktokens(source: &str) -> KTokenStream {
+        KTokenStream::new(&source.parse().unwrap())
+    }
+
+    #[test]
+    fn rewind_restores_the_position() {
+        let mut toks = ktokens("a b c");
+        toks.next();
+        let checkpoint = toks.checkpoint();
+        toks.next();
+        toks.next();
+        assert!(toks.is_end());
+
+        toks.rewind(checkpoint);
+        assert_eq!(toks.checkpoint(), checkpoint);
+        assert_eq!(toks.try_advance().unwrap().to_string(), "b");
+    }
+
+    #[test]
+    fn fork_leaves_the_original_cursor_unchanged() {
+        let toks = ktokens("pub struct Foo { a: u32 }");
+        let checkpoint = toks.checkpoint();
+
+        let mut fork = toks.fork();
+        fork.expect_tok("pub").unwrap();
+        fork.expect_tok("struct").unwrap();
+        fork.expect_ident().unwrap();
+        let mut fields = fork.expect_group().unwrap();
+        fields.expect_ident().unwrap();
+        assert!(fork.is_end());
+
+        assert_eq!(toks.checkpoint(), checkpoint);
+        assert_eq!(toks.peek().to_string(), "pub");
+        // the group is shared between the cursors, but the
+        // original one reads it from the beginning.
+        let mut toks = toks;
+        toks.next();
+        toks.next();
+        toks.next();
+        let mut fields = toks.expect_group().unwrap();
+        assert_eq!(fields.try_advance().unwrap().to_string(), "a");
+    }
+}
@@ -23,12 +23,15 @@ pub mod rust;
 ///
 /// The last case is particular useful when the parser
 /// is injected inside the procedural macro code, such
-/// as the linux kernel
+/// as the linux kernel.
+///
+/// The unit tests use always `proc_macro2`, because the
+/// `proc_macro` API is not available outside a procedural macro.
 pub mod proc_macro {
-    #[cfg(proc_macro_wrapper)]
+    #[cfg(any(proc_macro_wrapper, test))]
     extern crate proc_macro2 as macros;
 
-    #[cfg(any(not(proc_macro_wrapper), proc_macro))]
+    #[cfg(all(any(not(proc_macro_wrapper), proc_macro), not(test)))]
     extern crate proc_macro as macros;
 
     pub use macros::*;
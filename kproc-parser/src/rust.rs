@@ -4,11 +4,15 @@ pub mod ast_nodes;
 pub mod core;
 pub mod errors;
 pub mod fmt;
+pub mod kassoc;
 pub mod kattr;
 pub mod kenum;
+pub mod keywords;
 pub mod kfunc;
 pub mod kimpl;
 pub mod kparser;
+pub mod kpattern;
 pub mod kstruct;
 pub mod ktrait;
+pub mod ordered_map;
 pub mod ty;
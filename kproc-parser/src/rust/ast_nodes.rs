@@ -5,7 +5,6 @@
 //! Each implementation contains information
 //! regarding the position in `KDiagnostic`.
 
-use std::collections::HashMap;
 use std::fmt::Display;
 use std::rc::Rc;
 
@@ -15,7 +14,10 @@ use crate::kparser::{DummyTracer, KParserError};
 use crate::kproc_macros::KTokenStream;
 use crate::proc_macro::TokenTree;
 
-use super::fmt::{fmt_generics, fmt_ty};
+use super::fmt::{
+    fmt_bounds, fmt_generic_args, fmt_generics, fmt_impl_generics, fmt_lifetimes, fmt_ty,
+    fmt_ty_generics, fmt_where_clause,
+};
 use super::kenum::EnumToken;
 use super::kimpl::parse_impl;
 use super::kstruct::parse_struct;
@@ -105,21 +107,151 @@ impl TopLevelAST for EnumToken {
     }
 }
 
+/// The visibility of an item or a field, like `pub` or
+/// `pub(crate)`.
+///
+/// Reference <https://doc.rust-lang.org/stable/reference/visibility-and-privacy.html>
+///
+/// Visibility :
+///    pub
+///    | pub ( crate )
+///    | pub ( self )
+///    | pub ( super )
+///    | pub ( in SimplePath )
+#[derive(Debug, Clone, Default)]
+pub enum Visibility {
+    /// No visibility specified, so the item is private.
+    #[default]
+    Inherited,
+    /// `pub`
+    Public { pub_tok: TokenTree },
+    /// `pub(crate)`, where the scope is the `(crate)` group.
+    Crate {
+        pub_tok: TokenTree,
+        scope: TokenTree,
+    },
+    /// `pub(super)`
+    Super {
+        pub_tok: TokenTree,
+        scope: TokenTree,
+    },
+    /// `pub(self)`
+    SelfModule {
+        pub_tok: TokenTree,
+        scope: TokenTree,
+    },
+    /// `pub(in path)`, where the path is the tokens after `in`.
+    Restricted {
+        pub_tok: TokenTree,
+        scope: TokenTree,
+        path: Vec<TokenTree>,
+    },
+}
+
+impl Visibility {
+    pub fn is_inherited(&self) -> bool {
+        matches!(self, Visibility::Inherited)
+    }
+
+    /// return the `pub` token, or `None` if the visibility
+    /// is inherited.
+    pub fn pub_tok(&self) -> Option<&TokenTree> {
+        match self {
+            Visibility::Inherited => None,
+            Visibility::Public { pub_tok }
+            | Visibility::Crate { pub_tok, .. }
+            | Visibility::Super { pub_tok, .. }
+            | Visibility::SelfModule { pub_tok, .. }
+            | Visibility::Restricted { pub_tok, .. } => Some(pub_tok),
+        }
+    }
+}
+
+impl Display for Visibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Visibility::Inherited => Ok(()),
+            Visibility::Public { pub_tok } => write!(f, "{pub_tok}"),
+            Visibility::Crate { pub_tok, .. } => write!(f, "{pub_tok}(crate)"),
+            Visibility::Super { pub_tok, .. } => write!(f, "{pub_tok}(super)"),
+            Visibility::SelfModule { pub_tok, .. } => write!(f, "{pub_tok}(self)"),
+            Visibility::Restricted { pub_tok, path, .. } => {
+                let path = path.iter().map(|tok| tok.to_string()).collect::<String>();
+                write!(f, "{pub_tok}(in {path})")
+            }
+        }
+    }
+}
+
+impl From<&Visibility> for TokenStream {
+    fn from(value: &Visibility) -> Self {
+        value.to_string().parse().unwrap()
+    }
+}
+
 /// Strung token that allow to
 /// decode a `struct` block.
 ///
 /// Defined as described in
 /// https://doc.rust-lang.org/stable/reference/items/structs.html
-///
-// FIXME: parse and add where clause
-// FIXME: parse the TupleStruct
 #[derive(Debug)]
 pub struct StructToken {
-    pub attrs: HashMap<String, AttrToken>,
-    pub visibility: Option<TokenTree>,
+    pub attrs: Attributes,
+    pub visibility: Visibility,
     pub name: TokenTree,
+    /// The shape of the struct, and in the case of
+    /// a tuple struct the `fields` are positional.
+    pub kind: StructKind,
     pub fields: Vec<FieldToken>,
     pub generics: Option<GenericParams>,
+    pub where_clause: Option<WhereClause>,
+}
+
+impl StructToken {
+    /// return the doc comments of the struct, see `Attributes::docs`.
+    pub fn docs(&self) -> Option<String> {
+        self.attrs.docs()
+    }
+
+    /// check if the struct is a tuple struct like `struct Meters(pub f64);`
+    pub fn is_tuple(&self) -> bool {
+        matches!(self.kind, StructKind::Tuple)
+    }
+
+    /// check if the struct is a unit struct like `struct Marker;`
+    pub fn is_unit(&self) -> bool {
+        matches!(self.kind, StructKind::Unit)
+    }
+
+    /// split the generics of the struct in the parts that are
+    /// needed to generate an impl block for the struct.
+    pub fn split_for_impl(&self) -> (ImplGenerics<'_>, TypeGenerics<'_>, Option<&WhereClause>) {
+        match &self.generics {
+            Some(generics) => generics.split_for_impl(self.where_clause.as_ref()),
+            None => (
+                ImplGenerics::default(),
+                TypeGenerics::default(),
+                self.where_clause.as_ref(),
+            ),
+        }
+    }
+}
+
+/// The kind of struct declaration.
+///
+/// StructStruct :
+///    struct IDENTIFIER GenericParams? WhereClause? ( { StructFields? } | ; )
+///
+/// TupleStruct :
+///    struct IDENTIFIER GenericParams? ( TupleFields? ) WhereClause? ;
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StructKind {
+    /// `struct Foo { a: u32 }`
+    Named,
+    /// `struct Foo(u32);`
+    Tuple,
+    /// `struct Foo;`
+    Unit,
 }
 
 impl TopLevelAST for StructToken {
@@ -149,7 +281,40 @@ impl TryFrom<&TokenStream> for StructToken {
 
 impl Display for StructToken {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "")
+        if !self.visibility.is_inherited() {
+            write!(f, "{} ", self.visibility)?;
+        }
+        write!(f, "struct {}", self.name)?;
+        if let Some(generics) = &self.generics {
+            write!(f, "{generics}")?;
+        }
+        let fields = self
+            .fields
+            .iter()
+            .map(|field| field.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+        match self.kind {
+            StructKind::Named => {
+                if let Some(where_clause) = &self.where_clause {
+                    write!(f, " {where_clause}")?;
+                }
+                write!(f, " {{ {fields} }}")
+            }
+            StructKind::Tuple => {
+                write!(f, "({fields})")?;
+                if let Some(where_clause) = &self.where_clause {
+                    write!(f, " {where_clause}")?;
+                }
+                write!(f, ";")
+            }
+            StructKind::Unit => {
+                if let Some(where_clause) = &self.where_clause {
+                    write!(f, " {where_clause}")?;
+                }
+                write!(f, ";")
+            }
+        }
     }
 }
 
@@ -164,8 +329,8 @@ pub struct GenericParams {
 #[derive(Debug, Clone)]
 pub enum GenericParam {
     LifetimeParam(LifetimeParam),
-    TypeParam(TyToken),
-    Bounds(Bound), // FIXME: support the const params
+    TypeParam(TypeParam),
+    ConstParam(ConstParam),
 }
 
 impl GenericParam {
@@ -173,7 +338,8 @@ impl GenericParam {
         match self {
             Self::TypeParam(param) => param.bounds.push(bound),
             Self::LifetimeParam(param) => param.bounds.push(bound),
-            Self::Bounds(params) => params.add_bound(bound),
+            // a const param can not have bounds.
+            Self::ConstParam(_) => {}
         }
     }
 }
@@ -183,21 +349,46 @@ impl Display for GenericParam {
         match self {
             Self::LifetimeParam(param) => write!(f, "{param}"),
             Self::TypeParam(param) => write!(f, "{param}"),
-            Self::Bounds(bounds) => write!(f, "{bounds}"),
+            Self::ConstParam(param) => write!(f, "{param}"),
         }
     }
 }
 
+/// const N: usize = 3
+///
+/// ConstParam:
+///    const IDENTIFIER : Type ( = Block | IDENTIFIER | -?LITERAL )?
+#[derive(Debug, Clone)]
+pub struct ConstParam {
+    pub const_tok: TokenTree,
+    pub identifier: TokenTree,
+    pub ty: Box<TyToken>,
+    pub default: Option<TokenStream>,
+}
+
+impl std::fmt::Display for ConstParam {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}: {}", self.const_tok, self.identifier, self.ty)?;
+        if let Some(default) = &self.default {
+            write!(f, " = {default}")?;
+        }
+        Ok(())
+    }
+}
+
+/// TypeParamBound :
+///    Lifetime | TraitBound
 #[derive(Clone, Debug)]
 pub enum Bound {
     Lifetime(LifetimeParam),
-    Trait(TypeParam),
+    Trait(TraitBound),
 }
 
 impl Bound {
     pub fn add_bound(&mut self, bound: Bound) {
         match self {
-            Self::Trait(param) => param.bounds.push(bound),
+            // a trait bound can not have other bounds.
+            Self::Trait(_) => {}
             Self::Lifetime(param) => param.bounds.push(bound),
         }
     }
@@ -213,7 +404,84 @@ impl std::fmt::Display for Bound {
                 format!("{params}")
             }
         };
-        writeln!(f, "{code}")
+        write!(f, "{code}")
+    }
+}
+
+/// for<'a> ?Sized, Into<String> or Fn(&'a str) -> bool
+///
+/// TraitBound :
+///    ?? ForLifetimes? TypePath
+///    | ( ?? ForLifetimes? TypePath )
+#[derive(Clone, Debug)]
+pub struct TraitBound {
+    pub for_lifetimes: Vec<LifetimeParam>,
+    /// The `?` of a maybe bound like `?Sized`
+    pub maybe_tok: Option<TokenTree>,
+    /// The `const` of a `~const Trait` bound
+    pub const_tok: Option<TokenTree>,
+    /// The path of the trait with the generics arguments
+    pub path: Box<TyToken>,
+    /// The parenthesized arguments of the `Fn` traits,
+    /// like `(&'a str) -> bool` in `Fn(&'a str) -> bool`
+    pub fn_args: Option<ParenthesizedArgs>,
+}
+
+impl TraitBound {
+    /// check if the path of the trait ends with the path specified,
+    /// e.g: `std::fmt::Debug` is a path to `Debug`.
+    pub fn is_path_to(&self, path: &str) -> bool {
+        self.path.is_path_to(path)
+    }
+
+    /// check if the bound is a maybe bound like `?Sized`
+    pub fn is_maybe(&self) -> bool {
+        self.maybe_tok.is_some()
+    }
+}
+
+impl std::fmt::Display for TraitBound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.for_lifetimes.is_empty() {
+            write!(f, "for<{}> ", fmt_lifetimes(&self.for_lifetimes))?;
+        }
+        if self.maybe_tok.is_some() {
+            write!(f, "?")?;
+        }
+        if let Some(const_tok) = &self.const_tok {
+            write!(f, "~{const_tok} ")?;
+        }
+        write!(f, "{}", self.path)?;
+        if let Some(fn_args) = &self.fn_args {
+            write!(f, "{fn_args}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The arguments of the `Fn` traits like `(u8, u16) -> u32`
+///
+/// TypePathFn :
+///    ( TypePathFnInputs? ) (-> TypeNoBounds)?
+#[derive(Clone, Debug)]
+pub struct ParenthesizedArgs {
+    pub inputs: Vec<TyToken>,
+    pub output: Option<Box<TyToken>>,
+}
+
+impl std::fmt::Display for ParenthesizedArgs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|ty| ty.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+        write!(f, "({inputs})")?;
+        if let Some(output) = &self.output {
+            write!(f, " -> {output}")?;
+        }
+        Ok(())
     }
 }
 
@@ -228,39 +496,28 @@ impl std::fmt::Display for LifetimeParam {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut code = format!("'{}", self.lifetime_or_label);
         if !self.bounds.is_empty() {
-            code += &format!(
-                ": {}",
-                self.bounds
-                    .iter()
-                    .map(|bound| format!("{bound} +"))
-                    .collect::<String>()
-            );
-            code = code.strip_suffix('+').unwrap_or(&code).to_owned();
-            code += ",";
+            code += &format!(": {}", fmt_bounds(&self.bounds));
         }
         write!(f, "{code}")
     }
 }
 
+/// T: Debug = String
 #[derive(Debug, Clone)]
 pub struct TypeParam {
     pub identifier: TokenTree,
     pub bounds: Vec<Bound>,
+    pub default: Option<Box<TyToken>>,
 }
 
 impl std::fmt::Display for TypeParam {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut code = format!("{}", self.identifier);
         if !self.bounds.is_empty() {
-            code += &format!(
-                ": {}",
-                self.bounds
-                    .iter()
-                    .map(|bound| format!("{bound} +"))
-                    .collect::<String>()
-            );
-            code = code.strip_suffix('+').unwrap_or(&code).to_owned();
-            code += ",";
+            code += &format!(": {}", fmt_bounds(&self.bounds));
+        }
+        if let Some(default) = &self.default {
+            code += &format!(" = {default}");
         }
         write!(f, "{code}")
     }
@@ -273,22 +530,147 @@ impl Display for GenericParams {
     }
 }
 
+impl GenericParams {
+    /// split the generics declaration in the parts that are
+    /// needed to generate an impl block for the item, like
+    /// `impl<T: Debug> Trait for Foo<T> where T: Clone`.
+    pub fn split_for_impl<'a>(
+        &'a self,
+        where_clause: Option<&'a WhereClause>,
+    ) -> (ImplGenerics<'a>, TypeGenerics<'a>, Option<&'a WhereClause>) {
+        (
+            ImplGenerics {
+                generics: Some(self),
+            },
+            TypeGenerics {
+                generics: Some(self),
+            },
+            where_clause,
+        )
+    }
+}
+
+/// The generics of an impl block, like `<'a, T: Debug, const N: usize>`,
+/// declared with the bounds but without the defaults.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImplGenerics<'a> {
+    pub generics: Option<&'a GenericParams>,
+}
+
+impl Display for ImplGenerics<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.generics {
+            Some(generics) => write!(f, "{}", fmt_impl_generics(generics)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl From<ImplGenerics<'_>> for TokenStream {
+    fn from(value: ImplGenerics<'_>) -> Self {
+        value.to_string().parse().unwrap()
+    }
+}
+
+/// The generics of the type, like `<'a, T, N>`,
+/// with only the names of the parameters.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TypeGenerics<'a> {
+    pub generics: Option<&'a GenericParams>,
+}
+
+impl Display for TypeGenerics<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.generics {
+            Some(generics) => write!(f, "{}", fmt_ty_generics(generics)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl From<TypeGenerics<'_>> for TokenStream {
+    fn from(value: TypeGenerics<'_>) -> Self {
+        value.to_string().parse().unwrap()
+    }
+}
+
+/// Where clause token that allow to decode the
+/// `where` clause of an item, defined as described in
+/// https://doc.rust-lang.org/stable/reference/items/generics.html#where-clauses
+///
+/// WhereClause :
+///    where ( WhereClauseItem , )* WhereClauseItem ?
+#[derive(Debug, Clone)]
+pub struct WhereClause {
+    pub where_tok: TokenTree,
+    pub predicates: Vec<WherePredicate>,
+}
+
+impl Display for WhereClause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let code = fmt_where_clause(self);
+        write!(f, "{code}")
+    }
+}
+
+impl From<&WhereClause> for TokenStream {
+    fn from(value: &WhereClause) -> Self {
+        value.to_string().parse().unwrap()
+    }
+}
+
+/// WhereClauseItem :
+///    LifetimeWhereClauseItem
+///    | TypeBoundWhereClauseItem
+#[derive(Debug, Clone)]
+pub enum WherePredicate {
+    /// LifetimeWhereClauseItem like `'a: 'b + 'c`
+    Lifetime(LifetimeParam),
+    /// TypeBoundWhereClauseItem like `for<'a> T: Trait<'a> + 'a`
+    Type(Box<TypeBoundPredicate>),
+}
+
+/// TypeBoundWhereClauseItem :
+///    ForLifetimes? Type : TypeParamBounds?
+#[derive(Debug, Clone)]
+pub struct TypeBoundPredicate {
+    /// The higher-ranked lifetimes declared with `for<'a>`
+    pub for_lifetimes: Vec<LifetimeParam>,
+    pub bounded_ty: TyToken,
+    pub bounds: Vec<Bound>,
+}
+
 /// struct filed token allow to decode the
 /// struct fields defined as described in
 /// https://doc.rust-lang.org/stable/reference/items/structs.html
 #[derive(Debug)]
 pub struct FieldToken {
-    pub attrs: HashMap<String, AttrToken>,
-    pub visibility: Option<TokenTree>,
+    pub attrs: Attributes,
+    pub visibility: Visibility,
+    /// The name of the field, or in the case of a
+    /// tuple field the index literal (e.g: `0`), so
+    /// it is possible to generate `self.0` code.
     pub identifier: TokenTree,
     pub ty: TyToken,
 }
 
+impl FieldToken {
+    /// return the doc comments of the field, see `Attributes::docs`.
+    pub fn docs(&self) -> Option<String> {
+        self.attrs.docs()
+    }
+
+    /// check if the field is a positional field of a tuple struct.
+    pub fn is_positional(&self) -> bool {
+        matches!(self.identifier, TokenTree::Literal(_))
+    }
+}
+
 impl Display for FieldToken {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut vis = String::new();
-        if let Some(viss) = &self.visibility {
-            vis = viss.to_string()
+        let vis = &self.visibility;
+        if self.is_positional() {
+            return write!(f, "{} {}", vis, self.ty);
         }
         write!(f, "{} {}: {}", vis, self.identifier, self.ty)
     }
@@ -300,41 +682,204 @@ impl Display for FieldToken {
 /// https://doc.rust-lang.org/stable/reference/types.html#type-expressions
 #[derive(Debug, Clone)]
 pub enum TyKind {
+    /// `impl Trait + Send`, where all the bounds are stored
+    /// inside the `TyToken.bounds`, and the path of the first
+    /// trait is also copied inside the `TyToken`.
     ImplTrait,
-    Parenthesized,
+    /// `(T)`
+    Parenthesized(Box<TyToken>),
+    /// `dyn Trait + Send`, where all the bounds are stored
+    /// inside the `TyToken.bounds`, and the path of the first
+    /// trait is also copied inside the `TyToken`.
     TraitObject,
+    /// `Vec<T>`
     TypePath,
-    TupleType,
+    /// `(A, B)` and the unit type `()`
+    TupleType(Vec<TyToken>),
+    /// `!`
     NeverType,
-    RawPointerType,
-    ReferenceType,
-    ArrayType,
-    SliceType,
+    /// `*const T` or `*mut T`
+    RawPointerType { mutable: bool, ty: Box<TyToken> },
+    /// `&'a mut T`, where the `&`, the lifetime and the `mut`
+    /// are stored inside the `TyToken`.
+    ReferenceType(Box<TyToken>),
+    /// `[T; N]`
+    ArrayType { ty: Box<TyToken>, len: TokenStream },
+    /// `[T]`
+    SliceType(Box<TyToken>),
+    /// `_`
     InferredType,
-    QualifiedPathInType,
-    BareFunctionType,
-    MacroInvocation,
+    /// `<T as Trait>::Assoc`
+    QualifiedPathInType {
+        self_ty: Box<TyToken>,
+        trait_ty: Option<Box<TyToken>>,
+        segments: Vec<PathSegment>,
+    },
+    /// `unsafe extern "C" fn(u8) -> u8`
+    BareFunctionType(Box<BareFnTy>),
+    /// `my_type!(...)`
+    MacroInvocation {
+        path: Vec<TokenTree>,
+        tokens: TokenTree,
+    },
+}
+
+/// The segment of a path with the generics
+/// arguments, like `Assoc<T>` in `<T as Trait>::Assoc<T>`
+#[derive(Debug, Clone)]
+pub struct PathSegment {
+    pub ident: TokenTree,
+    pub generics: Option<Vec<GenericArg>>,
+}
+
+impl Display for PathSegment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.ident)?;
+        if let Some(generics) = &self.generics {
+            write!(f, "{}", fmt_generic_args(generics))?;
+        }
+        Ok(())
+    }
+}
+
+/// The generic argument of a type path, like
+/// `'a`, `T`, `3` and `Item = u8` in `Foo<'a, T, 3, Item = u8>`.
+///
+/// GenericArg :
+///    Lifetime | Type | GenericArgsConst | GenericArgsBinding
+#[derive(Debug, Clone)]
+pub enum GenericArg {
+    /// `'a`
+    Lifetime(LifetimeParam),
+    /// `T`
+    Type(TyToken),
+    /// `3`, `-1` or `{ N + 1 }`
+    Const(TokenStream),
+    /// `Item = u8` or `Item<T> = Vec<T>`
+    Binding {
+        ident: TokenTree,
+        generics: Option<Vec<GenericArg>>,
+        ty: TyToken,
+    },
+}
+
+impl GenericArg {
+    /// return the type of the argument if the
+    /// argument is a type.
+    pub fn ty(&self) -> Option<&TyToken> {
+        match self {
+            Self::Type(ty) => Some(ty),
+            _ => None,
+        }
+    }
+}
+
+impl Display for GenericArg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Lifetime(lifetime) => write!(f, "{lifetime}"),
+            Self::Type(ty) => write!(f, "{ty}"),
+            Self::Const(expr) => write!(f, "{expr}"),
+            Self::Binding {
+                ident,
+                generics,
+                ty,
+            } => {
+                write!(f, "{ident}")?;
+                if let Some(generics) = generics {
+                    write!(f, "{}", fmt_generic_args(generics))?;
+                }
+                write!(f, " = {ty}")
+            }
+        }
+    }
+}
+
+/// The signature of a function pointer type.
+///
+/// BareFunctionType :
+///    ForLifetimes? FunctionTypeQualifiers fn
+///        ( FunctionParametersMaybeNamedVariadic? ) BareFunctionReturnType?
+#[derive(Debug, Clone)]
+pub struct BareFnTy {
+    pub for_lifetimes: Vec<LifetimeParam>,
+    /// The qualifiers like `unsafe` or `extern "C"`
+    pub qualifiers: Vec<TokenTree>,
+    /// The parameters with an optional name
+    pub inputs: Vec<(Option<TokenTree>, TyToken)>,
+    pub output: Option<Box<TyToken>>,
 }
 
 /// parsing the type of the filed, where this will be
 /// defined with the following grammar
 /// https://doc.rust-lang.org/stable/reference/types.html#type-expressions
 ///
-// FIXME(vincenzopalazzo): I am not happy with this solution, so
-// happy to receive some feedback regarding it. In this case
-// would be good an enum or a filed regarding the kind of the type
+/// The information of the type are stored inside the `kind`,
+/// and only a reference, a trait object or an impl trait are
+/// flatten inside the `TyToken` itself.
 #[derive(Debug, Clone)]
 pub struct TyToken {
     pub kind: TyKind,
     pub ref_tok: Option<TokenTree>,
     pub mut_tok: Option<TokenTree>,
+    /// The name of the type, or the first token of the
+    /// type if the type do not have a name (e.g: a tuple).
     pub identifier: TokenTree,
     pub dyn_tok: Option<TokenTree>,
     pub lifetime: Option<LifetimeParam>,
-    pub generics: Option<Vec<TyToken>>,
+    pub generics: Option<Vec<GenericArg>>,
+    /// The leading `::` of a path like `::core::option::Option<T>`
+    pub leading_colon: Option<TokenTree>,
+    /// The segments of the type path, where the last one is
+    /// the `identifier` with the `generics` of the type.
+    ///
+    /// The segments are empty when the type is not a path.
+    pub segments: Vec<PathSegment>,
     pub bounds: Vec<Bound>,
 }
 
+impl TyToken {
+    /// create a new type token of the kind specified, without
+    /// references and generics.
+    pub fn new(kind: TyKind, identifier: TokenTree) -> Self {
+        TyToken {
+            kind,
+            ref_tok: None,
+            mut_tok: None,
+            identifier,
+            dyn_tok: None,
+            lifetime: None,
+            generics: None,
+            leading_colon: None,
+            segments: vec![],
+            bounds: vec![],
+        }
+    }
+
+    /// return the last segment of the type path, that is the
+    /// one that contains the name of the type.
+    pub fn last_segment(&self) -> Option<&PathSegment> {
+        self.segments.last()
+    }
+
+    /// check if the type path ends with the path specified,
+    /// e.g: `std::option::Option<T>` is a path to `Option` and
+    /// to `option::Option`.
+    pub fn is_path_to(&self, path: &str) -> bool {
+        let path = path
+            .trim_start_matches("::")
+            .split("::")
+            .collect::<Vec<_>>();
+        if path.len() > self.segments.len() {
+            return false;
+        }
+        self.segments[self.segments.len() - path.len()..]
+            .iter()
+            .zip(path)
+            .all(|(segment, name)| segment.ident.to_string() == name)
+    }
+}
+
 impl Display for TyToken {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let code = fmt_ty(self);
@@ -380,6 +925,7 @@ impl Display for TyToken {
 ///      }
 /// }
 /// ```
+#[allow(clippy::test_attr_in_doctest)]
 #[derive(Debug, Clone)]
 pub struct CondAttributeToken {
     /// name of the attribute
@@ -418,6 +964,7 @@ pub enum AttrToken {
     CondAttr(CondAttributeToken),
 }
 
+#[allow(deprecated)]
 impl AttrToken {
     // return the name of the current attribute,
     // to inspect the value please considerer to
@@ -451,7 +998,7 @@ impl AttrToken {
 /// Attribute token IR.
 ///
 /// Defined in <https://doc.rust-lang.org/stable/reference/attributes.html>
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum AttributeV2Token {
     /// InnerAttribute :
     ///  # ! [ Attr ]
@@ -476,10 +1023,10 @@ pub enum AttributeV2Token {
 /// | { TokenTree* }
 ///
 /// Defined in <https://doc.rust-lang.org/stable/reference/attributes.html>
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Attr {
-    /// The patch like `tokio::main` where for us
-    /// `main` is the identifier and `tokio::` is the patch
+    /// The path like `tokio::main` where for us
+    /// `main` is the identifier and `tokio` is the path
     pub path: Vec<TokenTree>,
     /// The name of the identifier inside the expression
     pub identifier: TokenTree,
@@ -491,6 +1038,405 @@ pub struct Attr {
     /// The value of the token tree if specifed
     /// e.g: `#[key="value"]` or `#[key(key=value)]`
     pub value: Option<Rc<Attr>>,
+    /// The structured content of the attribute, like
+    /// `cli(help = "x", short)`.
+    pub meta: Meta,
+}
+
+impl Attr {
+    /// check if the attribute matches `name`, that can be the
+    /// full path like `serde::rename` or the last segment
+    /// like `rename`.
+    pub fn is(&self, name: &str) -> bool {
+        self.meta.is(name)
+    }
+
+    /// return the nested items of the attribute when the
+    /// attribute is a list like `#[cli(short, long = "verbose")]`.
+    pub fn nested(&self) -> &[Meta] {
+        match &self.meta {
+            Meta::List { nested, .. } => nested,
+            _ => &[],
+        }
+    }
+
+    /// return the nested item with the given name.
+    pub fn find(&self, name: &str) -> Option<&Meta> {
+        self.nested().iter().find(|meta| meta.is(name))
+    }
+
+    /// return the string literal assigned to `name`,
+    /// like `x` in `#[cli(help = "x")]`.
+    pub fn get_str(&self, name: &str) -> Option<String> {
+        self.find(name)?.value_str()
+    }
+
+    /// return the boolean assigned to `name`, where the
+    /// flag form `#[cli(name)]` is considered `true`.
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        match self.find(name)? {
+            Meta::Path { .. } => Some(true),
+            meta => meta.value_bool(),
+        }
+    }
+
+    /// return the integer assigned to `name`,
+    /// like `3` in `#[cli(level = 3)]`.
+    pub fn get_int(&self, name: &str) -> Option<i64> {
+        self.find(name)?.value_int()
+    }
+
+    /// check if the attribute contains the flag `name`,
+    /// like `short` in `#[cli(short)]`.
+    pub fn has_flag(&self, name: &str) -> bool {
+        self.nested()
+            .iter()
+            .any(|meta| matches!(meta, Meta::Path { .. }) && meta.is(name))
+    }
+}
+
+/// Structured content of an attribute.
+///
+/// Meta :
+///   SimplePath
+/// | SimplePath = Expression
+/// | SimplePath ( (Meta (, Meta)* ,?)? )
+/// | Literal
+///
+/// The items of a list that do not follow this syntax, like
+/// `?Send` in `#[async_trait(?Send)]`, are kept as `Meta::Verbatim`.
+#[derive(Debug, Clone)]
+pub enum Meta {
+    /// A single word, like `short` in `#[cli(short)]`.
+    Path {
+        path: Vec<TokenTree>,
+        name: TokenTree,
+    },
+    /// A key value pair, like `help = "x"`, where the
+    /// value contains all the tokens of the expression.
+    NameValue {
+        path: Vec<TokenTree>,
+        name: TokenTree,
+        value: TokenStream,
+    },
+    /// A list of nested items, like `cli(short, help = "x")`.
+    List {
+        path: Vec<TokenTree>,
+        name: TokenTree,
+        nested: Vec<Meta>,
+    },
+    /// A literal inside a list, like `"x"` in `#[doc("x")]`.
+    Lit(TokenTree),
+    /// The raw tokens of an item inside a list that it is not
+    /// a meta, like `a + b` in `#[my_attr(a + b)]`.
+    Verbatim(TokenStream),
+}
+
+impl Meta {
+    /// return the last segment of the path of the item,
+    /// or the literal in case of `Meta::Lit`, and `None`
+    /// for `Meta::Verbatim`.
+    pub fn name(&self) -> Option<&TokenTree> {
+        match self {
+            Self::Lit(name) => Some(name),
+            Self::Path { name, .. } | Self::NameValue { name, .. } | Self::List { name, .. } => {
+                Some(name)
+            }
+            Self::Verbatim(_) => None,
+        }
+    }
+
+    /// return the segments before the name, like
+    /// `serde` in `serde::rename`.
+    pub fn path(&self) -> &[TokenTree] {
+        match self {
+            Self::Lit(_) | Self::Verbatim(_) => &[],
+            Self::Path { path, .. } | Self::NameValue { path, .. } | Self::List { path, .. } => {
+                path
+            }
+        }
+    }
+
+    /// return the full path of the item, like `serde::rename`,
+    /// or the raw tokens for `Meta::Verbatim`.
+    pub fn path_str(&self) -> String {
+        if let Self::Verbatim(tokens) = self {
+            return tokens.to_string();
+        }
+        let mut segments = self
+            .path()
+            .iter()
+            .map(|segment| segment.to_string())
+            .collect::<Vec<_>>();
+        segments.extend(self.name().map(|name| name.to_string()));
+        segments.join("::")
+    }
+
+    /// check if the item matches `name`, that can be the
+    /// full path like `serde::rename` or the last segment
+    /// like `rename`.
+    pub fn is(&self, name: &str) -> bool {
+        let (Self::Path { name: item, .. }
+        | Self::NameValue { name: item, .. }
+        | Self::List { name: item, .. }) = self
+        else {
+            return false;
+        };
+        let name = name.trim_start_matches("::");
+        if name.contains("::") {
+            self.path_str() == name
+        } else {
+            item.to_string() == name
+        }
+    }
+
+    /// return the value of a `name = value` item as string
+    /// when the value is a string literal.
+    pub fn value_str(&self) -> Option<String> {
+        match self {
+            Self::NameValue { value, .. } => parse_str_lit(&value.to_string()),
+            Self::Lit(lit) => parse_str_lit(&lit.to_string()),
+            _ => None,
+        }
+    }
+
+    pub fn value_bool(&self) -> Option<bool> {
+        let Self::NameValue { value, .. } = self else {
+            return None;
+        };
+        match value.to_string().as_str() {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        }
+    }
+
+    pub fn value_int(&self) -> Option<i64> {
+        let value = match self {
+            Self::NameValue { value, .. } => value.to_string(),
+            Self::Lit(lit) => lit.to_string(),
+            _ => return None,
+        };
+        parse_int_lit(&value)
+    }
+}
+
+/// unquote a string literal, like `"x"` or `r#"x"#`.
+fn parse_str_lit(lit: &str) -> Option<String> {
+    if let Some(raw) = lit.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let raw = &raw[hashes..raw.len().checked_sub(hashes)?];
+        return Some(raw.strip_prefix('"')?.strip_suffix('"')?.to_owned());
+    }
+    let lit = lit.strip_prefix('"')?.strip_suffix('"')?;
+    let mut value = String::new();
+    let mut chars = lit.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => value.push('\n'),
+            't' => value.push('\t'),
+            'r' => value.push('\r'),
+            '0' => value.push('\0'),
+            c @ ('\\' | '"' | '\'') => value.push(c),
+            'x' => {
+                let code = chars.as_str().get(..2)?;
+                let code = u8::from_str_radix(code, 16).ok().filter(u8::is_ascii)?;
+                value.push(char::from(code));
+                chars.nth(1);
+            }
+            'u' => {
+                let code = chars.as_str().strip_prefix('{')?;
+                let end = code.find('}')?;
+                let code = u32::from_str_radix(&code[..end].replace('_', ""), 16).ok()?;
+                value.push(char::from_u32(code)?);
+                chars.nth(end + 1);
+            }
+            // the line continuation skip the leading whitespaces
+            '\n' => chars = chars.as_str().trim_start().chars(),
+            _ => return None,
+        }
+    }
+    Some(value)
+}
+
+/// parse an integer literal by ignoring the `_` separators
+/// and the type suffix, like `1_000u32` or `0xFF_u8`.
+fn parse_int_lit(lit: &str) -> Option<i64> {
+    let lit = lit.replace([' ', '_'], "");
+    let (negative, lit) = match lit.strip_prefix('-') {
+        Some(lit) => (true, lit),
+        None => (false, lit.as_str()),
+    };
+    let (radix, lit) = match lit.get(..2) {
+        Some("0x") => (16, &lit[2..]),
+        Some("0o") => (8, &lit[2..]),
+        Some("0b") => (2, &lit[2..]),
+        _ => (10, lit),
+    };
+    let end = lit.find(|c: char| !c.is_digit(radix)).unwrap_or(lit.len());
+    let (digits, suffix) = lit.split_at(end);
+    let suffixes = [
+        "", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+    ];
+    if !suffixes.contains(&suffix) {
+        return None;
+    }
+    let value = i64::from_str_radix(digits, radix).ok()?;
+    Some(if negative { -value } else { value })
+}
+
+impl AttributeV2Token {
+    /// return the attribute wrapped by the token.
+    pub fn attr(&self) -> &Attr {
+        match self {
+            Self::InnerAttribute(attr) | Self::OuterAttribute(attr) => attr,
+        }
+    }
+
+    /// return the name of the attribute, like `cli`
+    /// for `#[cli(a)]`.
+    pub fn name(&self) -> String {
+        self.attr().identifier.to_string()
+    }
+
+    /// check if the attribute matches `name`, see `Attr::is`.
+    pub fn is(&self, name: &str) -> bool {
+        self.attr().is(name)
+    }
+
+    pub fn is_inner(&self) -> bool {
+        matches!(self, Self::InnerAttribute(_))
+    }
+}
+
+/// Ordered list of the attributes of an item.
+///
+/// Attributes with the same name are all kept in the
+/// order of declaration, so `#[cli(a)] #[cli(b)]` and
+/// multiple `#[doc = ...]` lines are not lost.
+#[derive(Debug, Clone, Default)]
+pub struct Attributes {
+    attrs: Vec<AttributeV2Token>,
+}
+
+impl Attributes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, attr: AttributeV2Token) {
+        self.attrs.push(attr);
+    }
+
+    /// return all the attributes with the given name, or
+    /// with the given full path, in declaration order.
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a AttributeV2Token> {
+        self.attrs.iter().filter(move |attr| attr.is(name))
+    }
+
+    /// return the first attribute with the given name.
+    pub fn first(&self, name: &str) -> Option<&AttributeV2Token> {
+        self.attrs.iter().find(|attr| attr.is(name))
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.first(name).is_some()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, AttributeV2Token> {
+        self.attrs.iter()
+    }
+
+    /// return the outer doc comments, like `/// foo` or
+    /// `#[doc = "foo"]`, joined by a new line and de-indented.
+    pub fn docs(&self) -> Option<String> {
+        collect_docs(self.attrs.iter().filter(|attr| !attr.is_inner()))
+    }
+
+    /// return the inner doc comments, like `//! foo` or
+    /// `#![doc = "foo"]`, joined by a new line and de-indented.
+    pub fn inner_docs(&self) -> Option<String> {
+        collect_docs(self.attrs.iter().filter(|attr| attr.is_inner()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.attrs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.attrs.is_empty()
+    }
+}
+
+/// join the lines of the `doc` attributes by removing the
+/// indentation that is common to all the non empty lines.
+fn collect_docs<'a>(attrs: impl Iterator<Item = &'a AttributeV2Token>) -> Option<String> {
+    let lines = attrs
+        .filter(|attr| attr.is("doc"))
+        .filter_map(|attr| attr.attr().meta.value_str())
+        .flat_map(|doc| doc.split('\n').map(str::to_owned).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    if lines.is_empty() {
+        return None;
+    }
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let docs = lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or("").trim_end())
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(docs)
+}
+
+impl Display for AttributeV2Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InnerAttribute(attr) => write!(f, "#!{}", attr.raw_attr),
+            Self::OuterAttribute(attr) => write!(f, "#{}", attr.raw_attr),
+        }
+    }
+}
+
+impl Display for Attributes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for attr in &self.attrs {
+            writeln!(f, "{attr}")?;
+        }
+        Ok(())
+    }
+}
+
+impl IntoIterator for Attributes {
+    type Item = AttributeV2Token;
+    type IntoIter = std::vec::IntoIter<AttributeV2Token>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.attrs.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Attributes {
+    type Item = &'a AttributeV2Token;
+    type IntoIter = std::slice::Iter<'a, AttributeV2Token>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.attrs.iter()
+    }
+}
+
+impl Extend<AttributeV2Token> for Attributes {
+    fn extend<I: IntoIterator<Item = AttributeV2Token>>(&mut self, iter: I) {
+        self.attrs.extend(iter);
+    }
 }
 
 /// AST Token to store information about an
@@ -499,14 +1445,24 @@ pub struct Attr {
 /// Reference: <https://doc.rust-lang.org/stable/reference/items/implementations.html>
 #[derive(Debug)]
 pub struct ImplToken {
-    pub attributes: HashMap<String, AttrToken>,
+    pub attributes: Attributes,
+    /// The `unsafe` token of an `unsafe impl`
+    pub unsafe_tok: Option<TokenTree>,
     pub generics: Option<GenericParams>,
-    /// The name of the impl Block
+    /// The `!` token of a negative impl like `impl !Send for Foo`
+    pub negative_tok: Option<TokenTree>,
+    /// The trait implemented by the impl block, that
+    /// is `None` in the case of an inherent impl.
+    pub trait_ty: Option<TyToken>,
+    /// The name of the type where the impl block is implemented for
     pub name: TokenTree,
-    /// for the type where the impl block is implemented for
-    // FIXME: we should abstract this token in a better way?
-    // or just rename it?
-    pub for_ty: Option<TyToken>,
+    /// The type where the impl block is implemented for,
+    /// so the `Self` type of the impl block.
+    pub self_ty: TyToken,
+    pub where_clause: Option<WhereClause>,
+    /// The inner attributes declared at the beginning
+    /// of the impl block, like `#![allow(unused)]`.
+    pub inn_attrs: Attributes,
     /// Content of the impl block
     ///
     /// It is stored the raw block because
@@ -514,9 +1470,35 @@ pub struct ImplToken {
     /// to parse this kind of token tree, and this
     /// will make a slim version of the library.
     pub raw_block: TokenStream,
+    pub associated_items: Vec<AssociatedItem>,
+    /// The functions declared inside the impl block, that
+    /// are also part of the `associated_items`.
     pub functions: Vec<MethodDeclToken>,
 }
 
+impl ImplToken {
+    /// return the doc comments of the impl block, see `Attributes::docs`.
+    pub fn docs(&self) -> Option<String> {
+        self.attributes.docs()
+    }
+
+    /// return the inner doc comments `//!` declared at the
+    /// beginning of the impl block.
+    pub fn inner_docs(&self) -> Option<String> {
+        self.inn_attrs.inner_docs()
+    }
+
+    /// check if the impl block is implementing a trait
+    pub fn is_trait_impl(&self) -> bool {
+        self.trait_ty.is_some()
+    }
+
+    /// check if the impl block is a negative impl like `impl !Send for Foo`
+    pub fn is_negative(&self) -> bool {
+        self.negative_tok.is_some()
+    }
+}
+
 impl TopLevelAST for ImplToken {
     fn span(&self) -> TokenTree {
         self.name.clone()
@@ -545,7 +1527,24 @@ impl Default for ImplToken {
 impl Display for ImplToken {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         // FIXME: print the attributes
-        write!(f, "impl {} {{ {} }}", self.name, self.raw_block)
+        if let Some(unsafe_tok) = &self.unsafe_tok {
+            write!(f, "{unsafe_tok} ")?;
+        }
+        write!(f, "impl")?;
+        if let Some(generics) = &self.generics {
+            write!(f, "{generics}")?;
+        }
+        if let Some(trait_ty) = &self.trait_ty {
+            if let Some(negative_tok) = &self.negative_tok {
+                write!(f, " {negative_tok}")?;
+            }
+            write!(f, " {trait_ty} for")?;
+        }
+        write!(f, " {}", self.self_ty)?;
+        if let Some(where_clause) = &self.where_clause {
+            write!(f, " {where_clause}")?;
+        }
+        write!(f, " {{ {} }}", self.raw_block)
     }
 }
 
@@ -555,16 +1554,36 @@ impl Display for ImplToken {
 /// Reference <https://doc.rust-lang.org/stable/reference/items/traits.html>
 #[derive(Debug)]
 pub struct TraitToken {
-    pub attrs: HashMap<String, AttrToken>,
-    pub visibility: Option<TokenTree>,
+    pub attrs: Attributes,
+    pub visibility: Visibility,
+    /// The `unsafe` token of an `unsafe trait`
+    pub unsafe_tok: Option<TokenTree>,
     pub ident: TokenTree,
     pub generics: Option<GenericParams>,
-    pub inn_attrs: Option<AttrToken>,
+    /// The supertraits, like `Clone + Debug` in `trait T: Clone + Debug`
+    pub bounds: Vec<Bound>,
+    pub where_clause: Option<WhereClause>,
+    /// The inner attributes declared at the beginning
+    /// of the trait block, like `#![allow(unused)]`.
+    pub inn_attrs: Attributes,
     pub associated_items: Vec<AssociatedItem>,
     pub raw_block: TokenStream,
     pub functions: Vec<MethodDeclToken>,
 }
 
+impl TraitToken {
+    /// return the doc comments of the trait, see `Attributes::docs`.
+    pub fn docs(&self) -> Option<String> {
+        self.attrs.docs()
+    }
+
+    /// return the inner doc comments `//!` declared at the
+    /// beginning of the trait block.
+    pub fn inner_docs(&self) -> Option<String> {
+        self.inn_attrs.inner_docs()
+    }
+}
+
 impl TopLevelAST for TraitToken {
     fn span(&self) -> TokenTree {
         self.ident.clone()
@@ -592,7 +1611,23 @@ impl Default for TraitToken {
 
 impl Display for TraitToken {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "")
+        if !self.visibility.is_inherited() {
+            write!(f, "{} ", self.visibility)?;
+        }
+        if let Some(unsafe_tok) = &self.unsafe_tok {
+            write!(f, "{unsafe_tok} ")?;
+        }
+        write!(f, "trait {}", self.ident)?;
+        if let Some(generics) = &self.generics {
+            write!(f, "{generics}")?;
+        }
+        if !self.bounds.is_empty() {
+            write!(f, ": {}", fmt_bounds(&self.bounds))?;
+        }
+        if let Some(where_clause) = &self.where_clause {
+            write!(f, " {where_clause}")?;
+        }
+        write!(f, " {{ {} }}", self.raw_block)
     }
 }
 
@@ -604,31 +1639,166 @@ impl Display for TraitToken {
 pub enum AssociatedItem {
     AssociatedFn(FnDeclTok),
     AssociatedMethod(MethodDeclToken),
-    // FIXME: add the other associatedItems missing
+    AssociatedConst(AssociatedConstToken),
+    AssociatedType(AssociatedTypeToken),
+    MacroInvocation(MacroInvocationToken),
+}
+
+impl AssociatedItem {
+    /// return the function declaration if the item is
+    /// an associated function or method.
+    pub fn as_fn(&self) -> Option<&MethodDeclToken> {
+        match self {
+            Self::AssociatedFn(method) | Self::AssociatedMethod(method) => Some(method),
+            _ => None,
+        }
+    }
+}
+
+impl Display for AssociatedItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AssociatedFn(item) => write!(f, "{item}"),
+            Self::AssociatedMethod(item) => write!(f, "{item}"),
+            Self::AssociatedConst(item) => write!(f, "{item}"),
+            Self::AssociatedType(item) => write!(f, "{item}"),
+            Self::MacroInvocation(item) => write!(f, "{item}"),
+        }
+    }
+}
+
+/// AST token to store the information about an
+/// associated constant like `const N: usize = 3;`
+///
+/// Reference <https://doc.rust-lang.org/stable/reference/items/constant-items.html>
+#[derive(Debug)]
+pub struct AssociatedConstToken {
+    pub attrs: Attributes,
+    pub visibility: Visibility,
+    /// The name of the constant, that can be also `_`
+    pub ident: TokenTree,
+    pub ty: TyToken,
+    /// The expression assigned to the constant, that
+    /// can be missing inside a trait declaration.
+    pub value: Option<TokenStream>,
+}
+
+impl Display for AssociatedConstToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.visibility.is_inherited() {
+            write!(f, "{} ", self.visibility)?;
+        }
+        write!(f, "const {}: {}", self.ident, self.ty)?;
+        if let Some(value) = &self.value {
+            write!(f, " = {value}")?;
+        }
+        write!(f, ";")
+    }
+}
+
+/// AST token to store the information about an
+/// associated type like `type Item: Clone = u8;`
+///
+/// Reference <https://doc.rust-lang.org/stable/reference/items/type-aliases.html>
+#[derive(Debug)]
+pub struct AssociatedTypeToken {
+    pub attrs: Attributes,
+    pub visibility: Visibility,
+    pub ident: TokenTree,
+    pub generics: Option<GenericParams>,
+    pub bounds: Vec<Bound>,
+    pub where_clause: Option<WhereClause>,
+    /// The type assigned to the associated type, that
+    /// can be missing inside a trait declaration.
+    pub default: Option<TyToken>,
+    /// The where clause declared after the type, like
+    /// `type Item<T> = Vec<T> where T: Clone;` inside an impl.
+    pub default_where_clause: Option<WhereClause>,
+}
+
+impl Display for AssociatedTypeToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.visibility.is_inherited() {
+            write!(f, "{} ", self.visibility)?;
+        }
+        write!(f, "type {}", self.ident)?;
+        if let Some(generics) = &self.generics {
+            write!(f, "{generics}")?;
+        }
+        if !self.bounds.is_empty() {
+            write!(f, ": {}", fmt_bounds(&self.bounds))?;
+        }
+        if let Some(where_clause) = &self.where_clause {
+            write!(f, " {where_clause}")?;
+        }
+        if let Some(default) = &self.default {
+            write!(f, " = {default}")?;
+        }
+        if let Some(where_clause) = &self.default_where_clause {
+            write!(f, " {where_clause}")?;
+        }
+        write!(f, ";")
+    }
+}
+
+/// AST token to store the information about a macro
+/// invocation inside an item block like `my_macro!(...);`
+///
+/// Reference <https://doc.rust-lang.org/stable/reference/macros.html#macro-invocation>
+#[derive(Debug)]
+pub struct MacroInvocationToken {
+    pub attrs: Attributes,
+    /// The path of the macro, e.g: `std::println`
+    pub path: Vec<TokenTree>,
+    /// The delimited token tree given as
+    /// input to the macro
+    pub tokens: TokenTree,
+}
+
+impl Display for MacroInvocationToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = self
+            .path
+            .iter()
+            .map(|tok| tok.to_string())
+            .collect::<String>();
+        write!(f, "{path}!{};", self.tokens)
+    }
 }
 
 /// AST token to store the information about the
 /// a function or method declaration
 ///
 /// Reference <https://doc.rust-lang.org/stable/reference/items/functions.html>
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MethodDeclToken {
-    pub attrs: HashMap<String, AttrToken>,
-    pub visibility: Option<TokenTree>,
-    // FIXME: use a better way to be able to
-    // identify what kind of qualifiers is
-    // specified.
-    pub qualifier: Option<TokenTree>,
+    pub attrs: Attributes,
+    pub visibility: Visibility,
+    /// The qualifiers like `const`, `async`, `unsafe` or
+    /// `extern "C"`, in the order of declaration.
+    pub qualifiers: Vec<TokenTree>,
     pub ident: TokenTree,
     pub generics: Option<GenericParams>,
     pub raw_params: TokenStream,
+    /// The `self` receiver of the method, that is
+    /// `Receiver::None` in the case of a function.
+    pub receiver: Receiver,
     /// method/function parameters parser
     /// from the `raw_params` in a tuple
     /// of `(identifier, Type Token)`
     /// and the position is identified by
     /// vector index.
+    ///
+    /// Only the parameters with a simple identifier
+    /// pattern (e.g: `x: u8` or `mut x: u8`) are
+    /// included, please use the `fn_params` to get
+    /// all the parameters.
     pub params: Vec<(TokenTree, TyToken)>,
+    /// method/function parameters (without the receiver)
+    /// in the order of declaration.
+    pub fn_params: Vec<FnParam>,
     pub return_ty: Option<TyToken>,
+    pub where_clause: Option<WhereClause>,
     pub raw_body: Option<TokenStream>,
 }
 
@@ -638,9 +1808,165 @@ impl Default for MethodDeclToken {
     }
 }
 
+impl MethodDeclToken {
+    /// return the doc comments of the function, see `Attributes::docs`.
+    pub fn docs(&self) -> Option<String> {
+        self.attrs.docs()
+    }
+
+    /// check if the declaration has a `self` receiver.
+    pub fn is_method(&self) -> bool {
+        !matches!(self.receiver, Receiver::None)
+    }
+}
+
+/// The `self` receiver of a method.
+///
+/// SelfParam :
+///    OuterAttribute* ( ShorthandSelf | TypedSelf )
+///
+/// ShorthandSelf :
+///    ( & | & Lifetime )? mut? self
+///
+/// TypedSelf :
+///    mut? self : Type
+///
+/// Reference <https://doc.rust-lang.org/stable/reference/items/associated-items.html#methods>
+#[derive(Debug, Clone, Default)]
+pub enum Receiver {
+    /// The declaration do not have a receiver, so it is a function.
+    #[default]
+    None,
+    /// `self`
+    Value {
+        attrs: Attributes,
+        self_tok: TokenTree,
+    },
+    /// `mut self`
+    MutValue {
+        attrs: Attributes,
+        self_tok: TokenTree,
+    },
+    /// `&self` or `&'a self`
+    Ref {
+        attrs: Attributes,
+        self_tok: TokenTree,
+        lifetime: Option<LifetimeParam>,
+    },
+    /// `&mut self` or `&'a mut self`
+    RefMut {
+        attrs: Attributes,
+        self_tok: TokenTree,
+        lifetime: Option<LifetimeParam>,
+    },
+    /// `self: Box<Self>` or `mut self: Box<Self>`
+    Typed {
+        attrs: Attributes,
+        mut_tok: Option<TokenTree>,
+        self_tok: TokenTree,
+        ty: Box<TyToken>,
+    },
+}
+
+impl Receiver {
+    /// return the attributes declared on the receiver, like
+    /// `#[allow(unused)] self`, or `None` if there is no receiver.
+    pub fn attrs(&self) -> Option<&Attributes> {
+        match self {
+            Self::None => None,
+            Self::Value { attrs, .. }
+            | Self::MutValue { attrs, .. }
+            | Self::Ref { attrs, .. }
+            | Self::RefMut { attrs, .. }
+            | Self::Typed { attrs, .. } => Some(attrs),
+        }
+    }
+
+    /// check if the method borrow `self`, so the receiver
+    /// is `&self` or `&mut self`.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, Self::Ref { .. } | Self::RefMut { .. })
+    }
+
+    /// check if the method take the ownership of `self`, so the
+    /// receiver is `self` or `mut self`.
+    ///
+    /// A typed receiver like `self: Box<Self>` is not considered,
+    /// because it is not possible to know it without resolving the type.
+    pub fn is_consumed(&self) -> bool {
+        matches!(self, Self::Value { .. } | Self::MutValue { .. })
+    }
+
+    /// check if the receiver is mutable.
+    pub fn is_mut(&self) -> bool {
+        match self {
+            Self::MutValue { .. } | Self::RefMut { .. } => true,
+            Self::Typed { mut_tok, .. } => mut_tok.is_some(),
+            _ => false,
+        }
+    }
+}
+
+impl Display for Receiver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // the receiver is inside the parameters, so the
+        // attributes are printed on the same line.
+        for attr in self.attrs().into_iter().flat_map(Attributes::iter) {
+            write!(f, "{attr} ")?;
+        }
+        match self {
+            Self::None => Ok(()),
+            Self::Value { self_tok, .. } => write!(f, "{self_tok}"),
+            Self::MutValue { self_tok, .. } => write!(f, "mut {self_tok}"),
+            Self::Ref {
+                self_tok, lifetime, ..
+            } => match lifetime {
+                Some(lifetime) => write!(f, "&{lifetime} {self_tok}"),
+                None => write!(f, "&{self_tok}"),
+            },
+            Self::RefMut {
+                self_tok, lifetime, ..
+            } => match lifetime {
+                Some(lifetime) => write!(f, "&{lifetime} mut {self_tok}"),
+                None => write!(f, "&mut {self_tok}"),
+            },
+            Self::Typed {
+                mut_tok,
+                self_tok,
+                ty,
+                ..
+            } => match mut_tok {
+                Some(mut_tok) => write!(f, "{mut_tok} {self_tok}: {ty}"),
+                None => write!(f, "{self_tok}: {ty}"),
+            },
+        }
+    }
+}
+
 impl Display for MethodDeclToken {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "")
+        write!(f, "{}", self.attrs)?;
+        if !self.visibility.is_inherited() {
+            write!(f, "{} ", self.visibility)?;
+        }
+        for qualifier in &self.qualifiers {
+            write!(f, "{qualifier} ")?;
+        }
+        write!(f, "fn {}", self.ident)?;
+        if let Some(generics) = &self.generics {
+            write!(f, "{generics}")?;
+        }
+        write!(f, "({})", self.raw_params)?;
+        if let Some(return_ty) = &self.return_ty {
+            write!(f, " -> {return_ty}")?;
+        }
+        if let Some(where_clause) = &self.where_clause {
+            write!(f, " {where_clause}")?;
+        }
+        match &self.raw_body {
+            Some(body) => write!(f, " {{ {body} }}"),
+            None => write!(f, ";"),
+        }
     }
 }
 
@@ -654,7 +1980,212 @@ impl TopLevelAST for MethodDeclToken {
     }
 }
 
+/// AST token to store the information about a
+/// function parameter like `(a, b): (u8, u8)`
+///
+/// FunctionParam :
+///    OuterAttribute* ( FunctionParamPattern | ... | Type )
+///
+/// FunctionParamPattern :
+///    PatternNoTopAlt : ( Type | ... )
+///
+/// Reference <https://doc.rust-lang.org/stable/reference/items/functions.html#function-parameters>
+#[derive(Debug, Clone)]
+pub struct FnParam {
+    pub attrs: Attributes,
+    pub pattern: Pattern,
+    pub ty: TyToken,
+}
+
+impl Display for FnParam {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.pattern, self.ty)
+    }
+}
+
+/// The pattern supported by the parser, that are
+/// the irrefutable patterns allowed inside the
+/// function parameters.
+///
+/// Reference <https://doc.rust-lang.org/stable/reference/patterns.html>
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    /// IdentifierPattern like `x`, `mut x`, `ref mut x` or `rest @ ..`
+    Ident {
+        ref_tok: Option<TokenTree>,
+        mut_tok: Option<TokenTree>,
+        ident: TokenTree,
+        /// The pattern after the `@`
+        subpattern: Option<Box<Pattern>>,
+    },
+    /// WildcardPattern `_`
+    Wildcard(TokenTree),
+    /// RestPattern `..`
+    Rest(TokenTree),
+    /// ReferencePattern like `&x` or `&mut x`
+    Reference {
+        ref_tok: TokenTree,
+        mut_tok: Option<TokenTree>,
+        pattern: Box<Pattern>,
+    },
+    /// TuplePattern like `(a, b)` or `(a,)`
+    Tuple(Vec<Pattern>),
+    /// GroupedPattern like `(a)`
+    Parenthesized(Box<Pattern>),
+    /// SlicePattern like `[a, b, ..]`
+    Slice(Vec<Pattern>),
+    /// StructPattern like `Point { x, y: other, .. }`
+    Struct {
+        path: Vec<TokenTree>,
+        fields: Vec<FieldPattern>,
+        rest: Option<TokenTree>,
+    },
+    /// TupleStructPattern like `Meters(value)`
+    TupleStruct {
+        path: Vec<TokenTree>,
+        patterns: Vec<Pattern>,
+    },
+}
+
+impl Pattern {
+    /// return the identifier in the case of a simple
+    /// identifier pattern like `x` or `mut x`.
+    pub fn ident(&self) -> Option<&TokenTree> {
+        match self {
+            Self::Ident {
+                ref_tok: None,
+                ident,
+                ..
+            } => Some(ident),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |patterns: &[Pattern]| {
+            patterns
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        let path = |path: &[TokenTree]| path.iter().map(|tok| tok.to_string()).collect::<String>();
+        match self {
+            Self::Ident {
+                ref_tok,
+                mut_tok,
+                ident,
+                subpattern,
+            } => {
+                if let Some(ref_tok) = ref_tok {
+                    write!(f, "{ref_tok} ")?;
+                }
+                if let Some(mut_tok) = mut_tok {
+                    write!(f, "{mut_tok} ")?;
+                }
+                write!(f, "{ident}")?;
+                if let Some(subpattern) = subpattern {
+                    write!(f, " @ {subpattern}")?;
+                }
+                Ok(())
+            }
+            Self::Wildcard(tok) => write!(f, "{tok}"),
+            Self::Rest(_) => write!(f, ".."),
+            Self::Reference {
+                ref_tok,
+                mut_tok,
+                pattern,
+            } => match mut_tok {
+                Some(mut_tok) => write!(f, "{ref_tok}{mut_tok} {pattern}"),
+                None => write!(f, "{ref_tok}{pattern}"),
+            },
+            Self::Tuple(patterns) if patterns.len() == 1 => write!(f, "({},)", join(patterns)),
+            Self::Tuple(patterns) => write!(f, "({})", join(patterns)),
+            Self::Parenthesized(pattern) => write!(f, "({pattern})"),
+            Self::Slice(patterns) => write!(f, "[{}]", join(patterns)),
+            Self::Struct {
+                path: p,
+                fields,
+                rest,
+            } => {
+                let mut fields = fields
+                    .iter()
+                    .map(|field| field.to_string())
+                    .collect::<Vec<String>>();
+                if rest.is_some() {
+                    fields.push("..".to_owned());
+                }
+                write!(f, "{} {{ {} }}", path(p), fields.join(", "))
+            }
+            Self::TupleStruct { path: p, patterns } => write!(f, "{}({})", path(p), join(patterns)),
+        }
+    }
+}
+
+/// The pattern of a single field inside a struct pattern
+/// like `x`, `ref mut x` or `x: other`.
+///
+/// StructPatternField :
+///    OuterAttribute* (
+///        TUPLE_INDEX : Pattern
+///        | IDENTIFIER : Pattern
+///        | ref? mut? IDENTIFIER
+///    )
+#[derive(Debug, Clone)]
+pub struct FieldPattern {
+    /// the name of the field, or the index in
+    /// the case of a tuple struct.
+    pub ident: TokenTree,
+    pub pattern: Pattern,
+    /// true if the field is declared with the shorthand
+    /// syntax like `x` or `ref mut x`
+    pub shorthand: bool,
+}
+
+impl Display for FieldPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.shorthand {
+            return write!(f, "{}", self.pattern);
+        }
+        write!(f, "{}: {}", self.ident, self.pattern)
+    }
+}
+
 /// from a parser point of view this
 /// should not change much because it is
 /// missing just a self param
 pub type FnDeclTok = MethodDeclToken;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn str_lit_escapes() {
+        assert_eq!(
+            parse_str_lit(r#""a\tb\"c\\""#).as_deref(),
+            Some("a\tb\"c\\")
+        );
+        assert_eq!(
+            parse_str_lit(r#""\x41\u{1F600}""#).as_deref(),
+            Some("A\u{1F600}")
+        );
+        assert_eq!(parse_str_lit("\"a\\\n    b\"").as_deref(), Some("ab"));
+        assert_eq!(parse_str_lit(r##"r#"\x41"#"##).as_deref(), Some("\\x41"));
+        assert_eq!(parse_str_lit(r#""\xFF""#), None);
+        assert_eq!(parse_str_lit(r#""\q""#), None);
+    }
+
+    #[test]
+    fn int_lit_radix() {
+        assert_eq!(parse_int_lit("1_000u32"), Some(1000));
+        assert_eq!(parse_int_lit("0xFF"), Some(255));
+        assert_eq!(parse_int_lit("0xff_u8"), Some(255));
+        assert_eq!(parse_int_lit("0o17"), Some(15));
+        assert_eq!(parse_int_lit("0b1010_1010"), Some(170));
+        assert_eq!(parse_int_lit("- 0x10"), Some(-16));
+        assert_eq!(parse_int_lit("0xG"), None);
+    }
+}
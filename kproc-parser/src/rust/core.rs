@@ -4,201 +4,377 @@
 use crate::kparser::KParserError;
 use crate::kparser::{self, KParserTracer};
 use crate::kproc_macros::{KTokenStream, MatchTok};
-use crate::proc_macro::TokenTree;
-use crate::rust::ast_nodes::{self, TypeParam};
-use crate::{build_error, check, trace};
+use crate::proc_macro::{Delimiter, Spacing, TokenStream, TokenTree};
+use crate::rust::ast_nodes::TypeParam;
+use crate::{build_error, trace};
 
-use super::ast_nodes::{GenericParam, GenericParams, LifetimeParam, TyToken};
-use super::ty::parse_ty;
+use super::keywords::is_keyword_tok;
+
+use super::ast_nodes::{
+    Bound, ConstParam, GenericParam, GenericParams, LifetimeParam, TyToken, TypeBoundPredicate,
+    Visibility, WhereClause, WherePredicate,
+};
+use super::ty::{parse_trait_bound, parse_ty};
 
 /// parsing the declaration of the lifetimes and generics for a
-/// declaration of a impl block or struct.
+/// declaration of an item, like a struct, an enum, a fn, an impl
+/// block or a trait.
+///
+/// GenericParams :
+///    < >
+///    | < (GenericParam ,)* GenericParam ,? >
+///
+/// Compliant to <https://doc.rust-lang.org/stable/reference/items/generics.html>
 pub fn check_and_parse_generics_params(
     ast: &mut KTokenStream,
     tracer: &dyn KParserTracer,
 ) -> kparser::Result<Option<GenericParams>> {
     trace!(tracer, "parsing generics params");
-    // compliant to this https://doc.rust-lang.org/stable/reference/items/generics.html
-    if ast.match_tok("<") {
-        ast.next(); // consume `<``
-        let mut generics = vec![];
-        while !ast.match_tok(">") {
-            trace!(tracer, "iterate over geeneric, stuck on {:?}", ast.peek());
-            if let Some(lifetime) = check_and_parse_lifetime(ast) {
-                let param = LifetimeParam {
-                    lifetime_or_label: lifetime,
-                    bounds: Vec::new(),
-                };
-                generics.push(GenericParam::LifetimeParam(param));
-            } else if let Some(ty) = parse_ty(ast, tracer)? {
-                generics.push(GenericParam::TypeParam(ty));
-            }
+    if ast.is_end() {
+        return Ok(None);
+    }
+    // the generics can be wrapped inside an invisible group
+    // when the item is generated by a `macro_rules!`.
+    if is_generics_group(ast) {
+        trace!(tracer, "in a `<...>` token group, uwrapping it ...");
+        let mut inner_stream = ast.expect_group()?;
+        return check_and_parse_generics_params(&mut inner_stream, tracer);
+    }
+    if !ast.match_tok("<") {
+        return Ok(None);
+    }
+    ast.next(); // consume `<``
+    let mut generics = vec![];
+    while !ast.match_tok(">") {
+        trace!(
+            tracer,
+            "iterate over geeneric, stuck on {:?}",
+            ast.try_peek()?
+        );
+        let param = parse_generic_param(ast, tracer)?;
+        trace!(tracer, "generic param found `{param}`");
+        generics.push(param);
+        // the `,` after a default type is already consumed
+        // by the type parser.
+        if ast.next_if(|tok| tok.match_tok(",")).is_none()
+            && !ast.match_tok(">")
+            && !ast.try_prev()?.match_tok(",")
+        {
+            let tok = ast.try_peek()?;
+            return Err(build_error!(
+                tok.clone(),
+                "expected `,` or `>` but got `{tok}`"
+            ));
         }
-        ast.next(); // consume the `>` toks
-        return Ok(Some(GenericParams { params: generics }));
     }
-    Ok(None)
+    ast.expect_punct('>')?;
+    Ok(Some(GenericParams { params: generics }))
 }
 
+/// GenericParam :
+///    OuterAttribute* ( LifetimeParam | TypeParam | ConstParam )
+fn parse_generic_param(
+    ast: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<GenericParam> {
+    if let Some(lifetime) = check_and_parse_lifetime(ast) {
+        let mut param = LifetimeParam {
+            lifetime_or_label: lifetime,
+            bounds: Vec::new(),
+        };
+        if ast.match_tok(":") {
+            ast.next(); // consume `:`
+            param.bounds = parse_type_param_bounds(ast, tracer)?;
+        }
+        return Ok(GenericParam::LifetimeParam(param));
+    }
+    if check_keyword(ast, "const", 0) {
+        let param = parse_const_param(ast, tracer)?;
+        return Ok(GenericParam::ConstParam(param));
+    }
+    let identifier = ast.try_advance()?;
+    if !matches!(identifier, TokenTree::Ident(_)) {
+        return Err(build_error!(
+            identifier,
+            "expected a generic parameter, but found `{identifier}`"
+        ));
+    }
+    let mut param = TypeParam {
+        identifier,
+        bounds: vec![],
+        default: None,
+    };
+    if ast.match_tok(":") {
+        ast.next(); // consume `:`
+        param.bounds = parse_type_param_bounds(ast, tracer)?;
+    }
+    if ast.match_tok("=") {
+        let eq_tok = ast.try_advance()?;
+        // the `,` after the default type is consumed by the type parser
+        let default = parse_ty(ast, tracer)?.ok_or(build_error!(
+            eq_tok,
+            "failing to parse the default type, this is a bug, please report it"
+        ))?;
+        param.default = Some(Box::new(default));
+    }
+    Ok(GenericParam::TypeParam(param))
+}
+
+#[deprecated(note = "Please use the check_and_parse_generics_params")]
 pub fn check_and_parse_bounds(
     stream: &mut KTokenStream,
     tracer: &dyn KParserTracer,
 ) -> kparser::Result<Option<GenericParams>> {
-    let is_starting_tok = stream.match_tok("<");
-    if !is_starting_tok && !stream.is_group() {
-        trace!(
-            tracer,
-            "while checking the parameter we do not find a token group"
-        );
-        return Ok(None);
+    check_and_parse_generics_params(stream, tracer)
+}
+
+/// parse the declaration of a const generic parameter
+/// like `const N: usize = 3`, the `,` after the parameter
+/// is not consumed.
+///
+/// ConstParam:
+///    const IDENTIFIER : Type ( = Block | IDENTIFIER | -?LITERAL )?
+pub fn parse_const_param(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<ConstParam> {
+    let const_tok = stream.expect_tok("const")?;
+    let identifier = stream.expect_ident()?;
+    let separator = stream.expect_punct(':')?;
+    let ty = parse_ty(stream, tracer)?.ok_or(build_error!(
+        separator,
+        "failing to parse the type of the const param, this is a bug, please report it"
+    ))?;
+    let default = match stream.next_if(|tok| tok.match_tok("=")) {
+        Some(eq_tok) => Some(parse_const_param_default(stream, eq_tok)?),
+        None => None,
+    };
+    trace!(tracer, "const param `{identifier}: {ty}`");
+    Ok(ConstParam {
+        const_tok,
+        identifier,
+        ty: Box::new(ty),
+        default,
+    })
+}
+
+/// parse the default value of a const param after the `=`, that
+/// is a block, a literal, a negative literal or a path, otherwise
+/// the error points to the `=`.
+fn parse_const_param_default(
+    stream: &mut KTokenStream,
+    eq_tok: TokenTree,
+) -> kparser::Result<TokenStream> {
+    let missing_value = || build_error!(eq_tok.clone(), "expected a value after `=`");
+    let mut default = vec![];
+    match stream.try_peek().map_err(|_| missing_value())? {
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+            default.push(stream.try_advance()?);
+        }
+        TokenTree::Literal(_) => default.push(stream.try_advance()?),
+        TokenTree::Punct(punct) if punct.as_char() == '-' => {
+            default.push(stream.try_advance()?);
+            match stream.next_if(|tok| matches!(tok, TokenTree::Literal(_))) {
+                Some(literal) => default.push(literal),
+                None => return Err(missing_value()),
+            }
+        }
+        TokenTree::Ident(_) => {
+            default.push(stream.try_advance()?);
+            while check_path_separator(stream, 0) {
+                default.push(stream.try_advance()?);
+                default.push(stream.try_advance()?);
+                default.push(stream.expect_ident()?);
+            }
+        }
+        _ => return Err(missing_value()),
     }
-    if !is_starting_tok && !stream.peek().clone().to_token_stream().match_tok("<") {
-        trace!(tracer, "not a `<...>` token group`");
+    Ok(TokenStream::from_iter(default))
+}
+
+/// parse the where clause of an item if it is present, otherwise
+/// return `None`.
+///
+/// The clause finish with the body of the item (`{ ... }`), with
+/// the `;`, with the `=` of a type alias or with the end of the
+/// stream, and all the stop tokens are not consumed.
+///
+/// WhereClause :
+///    where ( WhereClauseItem , )* WhereClauseItem ?
+pub fn check_and_parse_where_clause(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<Option<WhereClause>> {
+    if !check_keyword(stream, "where", 0) {
         return Ok(None);
     }
+    let where_tok = stream.try_advance()?;
+    trace!(tracer, "parsing where clause");
+    let mut predicates = vec![];
+    while !is_where_clause_end(stream) {
+        let predicate = if let Some(lifetime) = check_and_parse_lifetime(stream) {
+            stream.expect_punct(':')?;
+            let bounds = parse_type_param_bounds(stream, tracer)?;
+            WherePredicate::Lifetime(LifetimeParam {
+                lifetime_or_label: lifetime,
+                bounds,
+            })
+        } else {
+            let for_lifetimes = check_and_parse_for_lifetimes(stream)?.unwrap_or_default();
+            let start = stream.try_peek()?.clone();
+            let bounded_ty = parse_ty(stream, tracer)?.ok_or(build_error!(
+                start,
+                "failing to parse the type inside the where clause, this is a bug, please report it"
+            ))?;
+            stream.expect_punct(':')?;
+            let bounds = parse_type_param_bounds(stream, tracer)?;
+            WherePredicate::Type(Box::new(TypeBoundPredicate {
+                for_lifetimes,
+                bounded_ty,
+                bounds,
+            }))
+        };
+        trace!(tracer, "where predicate found `{:?}`", predicate);
+        predicates.push(predicate);
+        if !stream.is_end() && stream.match_tok(",") {
+            stream.next();
+        }
+    }
+    Ok(Some(WhereClause {
+        where_tok,
+        predicates,
+    }))
+}
 
-    let inner_stream = stream;
-    if !is_starting_tok {
-        trace!(tracer, "in a `<...>` token group, uwrapping it ...");
-        *inner_stream = inner_stream.advance().to_token_stream();
+fn is_where_clause_end(stream: &KTokenStream) -> bool {
+    if stream.is_end() || stream.match_tok(";") || stream.match_tok("=") {
+        return true;
     }
+    matches!(stream.peek(), TokenTree::Group(group) if group.delimiter() == Delimiter::Brace)
+}
 
-    if inner_stream.match_tok("<") {
-        trace!(tracer, "check and parsing the type bounds");
-        inner_stream.next(); // consume `<``
+/// parse the higher-ranked lifetimes declaration like `for<'a, 'b>`,
+/// if is not present return `None`.
+///
+/// ForLifetimes :
+///    for GenericParams
+pub fn check_and_parse_for_lifetimes(
+    stream: &mut KTokenStream,
+) -> kparser::Result<Option<Vec<LifetimeParam>>> {
+    if !check_keyword(stream, "for", 0) || !check_tok(stream, "<", 1) {
+        return Ok(None);
+    }
+    stream.next(); // consume `for`
+    stream.next(); // consume `<`
+    let mut lifetimes = vec![];
+    while !stream.match_tok(">") {
+        let Some(lifetime) = check_and_parse_lifetime(stream) else {
+            return Err(build_error!(
+                stream.try_peek()?.clone(),
+                "only lifetimes are allowed inside the `for<...>`"
+            ));
+        };
+        lifetimes.push(LifetimeParam {
+            lifetime_or_label: lifetime,
+            bounds: vec![],
+        });
+        if stream.match_tok(",") {
+            stream.next();
+        }
+    }
+    stream.expect_punct('>')?;
+    Ok(Some(lifetimes))
+}
 
-        let mut generics = vec![];
-        while !inner_stream.is_end() && !inner_stream.match_tok(">") {
-            trace!(
-                tracer,
-                "iterate over tokens, current token is: `{:?}`",
-                inner_stream.peek()
-            );
-            let mut generic: Option<GenericParam> = None;
-            while !inner_stream.match_tok(",") && !inner_stream.match_tok(">") {
-                trace!(tracer, "checking bound");
-                match inner_stream.peek().to_string().as_str() {
-                    "+" | ":" => {
-                        let tok = inner_stream.advance();
-                        assert!(
-                            ["+", ":"].contains(&tok.to_string().as_str()),
-                            "unexpected token {:?}",
-                            tok.to_string()
-                        );
-                        trace!(tracer, "new bound for the current trait");
-                        let bound = if let Some(lifetime) = check_and_parse_lifetime(inner_stream) {
-                            ast_nodes::Bound::Lifetime(LifetimeParam {
-                                lifetime_or_label: lifetime,
-                                bounds: Vec::new(),
-                            })
-                        } else {
-                            let trait_bound = inner_stream.advance();
-                            ast_nodes::Bound::Trait(TypeParam {
-                                identifier: trait_bound,
-                                bounds: Vec::new(),
-                            })
-                        };
-                        assert!(
-                            generic.is_some(),
-                            "concatenation bound `+` on generic used in the wrong way"
-                        );
-                        trace!(tracer, "bound found `{:?}`", bound);
-                        let Some(generic) = generic.as_mut() else {
-                            return Err(build_error!(
-                                inner_stream.peek().clone(),
-                                "concatenation bound `+` on generic used in the wrong way"
-                            ));
-                        };
-                        generic.add_bound(bound);
-                    }
-                    _ => {
-                        assert!(
-                            generic.is_none(),
-                            "declaration bound with `:` used in the wrong way"
-                        );
-                        trace!(tracer, "parising token {:?}", inner_stream.peek());
-                        if let Some(lifetime) = check_and_parse_lifetime(inner_stream) {
-                            trace!(tracer, "life bound found {:?}", lifetime);
-                            generic = Some(GenericParam::LifetimeParam(LifetimeParam {
-                                lifetime_or_label: lifetime,
-                                bounds: vec![],
-                            }));
-                            continue;
-                        }
-                        let identifier = inner_stream.advance();
-                        trace!(tracer, "Trait `{identifier}`");
-                        generic = Some(GenericParam::Bounds(ast_nodes::Bound::Trait(TypeParam {
-                            identifier,
-                            bounds: vec![],
-                        })))
-                    }
-                }
-                trace!(tracer, "next token `{:?}`", inner_stream.peek());
-            }
-            trace!(
-                tracer,
-                "conclude to parse the generic bound `{:?}`",
-                generic
-            );
-            generics.push(generic.unwrap());
-            if inner_stream.match_tok(",") {
-                check!(",", inner_stream.advance())?;
-            }
+/// parse a sequence of bounds separated by `+`, the sequence
+/// stop when the next token is not a `+`, or when after the
+/// `+` there is not a bound.
+///
+/// TypeParamBounds :
+///    TypeParamBound ( + TypeParamBound )* +?
+pub fn parse_type_param_bounds(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<Vec<Bound>> {
+    let mut bounds = vec![];
+    while is_bound_start(stream) {
+        let bound = parse_type_param_bound(stream, tracer)?;
+        trace!(tracer, "bound found `{bound}`");
+        bounds.push(bound);
+        if stream.is_end() || !stream.match_tok("+") {
+            break;
         }
-        trace!(
-            tracer,
-            "finish to parse the generics bounds `{:?}`",
-            generics
-        );
-        if !inner_stream.is_end() {
-            check!(">", inner_stream.advance())?; // consume the `>` toks
+        stream.next(); // consume `+`
+    }
+    Ok(bounds)
+}
+
+/// TypeParamBound :
+///    Lifetime | TraitBound
+pub fn parse_type_param_bound(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<Bound> {
+    if let Some(lifetime) = check_and_parse_lifetime(stream) {
+        return Ok(Bound::Lifetime(LifetimeParam {
+            lifetime_or_label: lifetime,
+            bounds: vec![],
+        }));
+    }
+    Ok(Bound::Trait(parse_trait_bound(stream, tracer)?))
+}
+
+/// check if the next token can be the start of a bound.
+fn is_bound_start(stream: &KTokenStream) -> bool {
+    if stream.is_end() {
+        return false;
+    }
+    match stream.peek() {
+        tok @ TokenTree::Ident(_) => !is_keyword_tok(tok, "where"),
+        TokenTree::Punct(punct) => {
+            ['\'', '?', '~'].contains(&punct.as_char()) || check_path_separator(stream, 0)
         }
-        return Ok(Some(GenericParams { params: generics }));
+        _ => false,
+    }
+}
+
+/// check if the current token is an invisible group that
+/// starts with `<`, without flattening any other group.
+fn is_generics_group(ast: &KTokenStream) -> bool {
+    match ast.try_peek() {
+        Ok(TokenTree::Group(group)) if group.delimiter() == Delimiter::None => ast
+            .opt_ktoken_stream()
+            .is_some_and(|stream| stream.match_tok("<")),
+        _ => false,
     }
-    Ok(None)
 }
 
 /// helper function that check and parse the reference token `&`, if
 /// is not present return `None`.
 pub fn check_and_parse_ref(ast: &mut KTokenStream) -> Option<TokenTree> {
-    let token = ast.peek();
-    match token.to_string().as_str() {
-        "&" => Some(ast.advance()),
-        _ => None,
-    }
+    ast.next_if(|tok| tok.match_tok("&"))
 }
 
 /// helper function that check and parse the lifetime symbol `'`, if
 /// is not present return `None`.
 pub fn check_and_parse_lifetime(ast: &mut KTokenStream) -> Option<TokenTree> {
-    let token = ast.peek().to_string();
-    match token.as_str() {
-        "'" => {
-            ast.next();
-            Some(ast.advance())
-        }
-        _ => None,
+    if !ast.has(1) || !ast.match_tok("'") {
+        return None;
     }
+    ast.next();
+    ast.try_advance().ok()
 }
 
 /// helper function that check and parse the `mut` token, if is not
 /// present return `None`.
 pub fn check_and_parse_mut(ast: &mut KTokenStream) -> Option<TokenTree> {
-    let token = ast.peek().to_string();
-    match token.as_str() {
-        "mut" => Some(ast.advance()),
-        _ => None,
-    }
+    ast.next_if(|tok| is_keyword_tok(tok, "mut"))
 }
 
 /// helper function that check and parser the `dyn` token, if is not
 /// present return `None`.
 pub fn check_and_parse_dyn(ast: &mut KTokenStream) -> Option<TokenTree> {
-    let token = ast.peek().to_string();
-    match token.as_str() {
-        "dyn" => Some(ast.advance()),
-        _ => None,
-    }
+    ast.next_if(|tok| is_keyword_tok(tok, "dyn"))
 }
 
 #[macro_export]
@@ -208,56 +384,130 @@ macro_rules! parse_visibility {
     }};
 }
 
-/// parse visibility identifier like `pub(crate)`` and return an option
-/// value in case it is not defined.
-pub fn check_and_parse_visibility(toks: &mut KTokenStream) -> Option<TokenTree> {
-    if check_identifier(toks, "pub", 0) {
-        return Some(toks.advance());
-    }
-    None
+/// parse visibility identifier like `pub(crate)` and return
+/// `Visibility::Inherited` in case it is not defined.
+///
+/// The group after `pub` is consumed only when it is a valid
+/// scope, so the tuple field `pub (u32, u8)` is not a visibility.
+pub fn check_and_parse_visibility(toks: &mut KTokenStream) -> Visibility {
+    let Some(pub_tok) = toks.next_if(|tok| is_keyword_tok(tok, "pub")) else {
+        return Visibility::Inherited;
+    };
+    let scope = match toks.try_peek() {
+        Ok(scope @ TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            scope.clone()
+        }
+        _ => return Visibility::Public { pub_tok },
+    };
+    let Some(mut scope_stream) = toks.opt_ktoken_stream() else {
+        return Visibility::Public { pub_tok };
+    };
+    let visibility = if check_keyword(&scope_stream, "in", 0) && scope_stream.has(1) {
+        scope_stream.next(); // consume `in`
+        let mut path = Vec::new();
+        while let Ok(tok) = scope_stream.try_advance() {
+            path.push(tok);
+        }
+        Visibility::Restricted {
+            pub_tok,
+            scope,
+            path,
+        }
+    } else if scope_stream.has(1) {
+        return Visibility::Public { pub_tok };
+    } else if check_keyword(&scope_stream, "crate", 0) {
+        Visibility::Crate { pub_tok, scope }
+    } else if check_keyword(&scope_stream, "super", 0) {
+        Visibility::Super { pub_tok, scope }
+    } else if check_keyword(&scope_stream, "self", 0) {
+        Visibility::SelfModule { pub_tok, scope }
+    } else {
+        return Visibility::Public { pub_tok };
+    };
+    toks.next(); // consume the scope group
+    visibility
 }
 
+#[deprecated(note = "please use `check_and_parse_fn_qualifiers`, that parse all the qualifiers")]
 pub fn check_and_parse_fn_qualifier(toks: &mut KTokenStream) -> Option<TokenTree> {
-    if check_identifiers(toks, &["async", "const", "unsafe"], 0) {
-        return Some(toks.advance());
+    if check_keywords(toks, &["async", "const", "unsafe"], 0) {
+        return toks.try_advance().ok();
     }
     None
 }
 
+/// parse the qualifiers of a function in the order required
+/// by the language, including the ABI of the `extern`.
+///
+/// FunctionQualifiers :
+///    const? async? unsafe? (extern Abi?)?
+pub fn check_and_parse_fn_qualifiers(toks: &mut KTokenStream) -> Vec<TokenTree> {
+    let mut qualifiers = Vec::new();
+    for keyword in ["const", "async", "unsafe", "extern"] {
+        if let Some(qualifier) = toks.next_if(|tok| is_keyword_tok(tok, keyword)) {
+            qualifiers.push(qualifier);
+        }
+    }
+    // the ABI of the extern like `extern "C"`
+    if qualifiers
+        .last()
+        .is_some_and(|tok| is_keyword_tok(tok, "extern"))
+    {
+        if let Some(abi) = toks.next_if(|tok| matches!(tok, TokenTree::Literal(_))) {
+            qualifiers.push(abi);
+        }
+    }
+    qualifiers
+}
+
 pub fn check_and_parse_fn_tok(toks: &mut KTokenStream) -> Option<TokenTree> {
-    if check_identifier(toks, "fn", 0) {
-        return Some(toks.advance());
+    if check_keyword(toks, "fn", 0) {
+        return toks.try_advance().ok();
     }
     None
 }
 
 pub fn check_is_fun_with_visibility(toks: &mut KTokenStream) -> bool {
-    if check_identifier(toks, "pub", 0) {
-        if check_identifiers(toks, &["async", "const", "unsafe"], 1) {
-            return check_identifier(toks, "fn", 2);
-        } else if check_identifier(toks, "fn", 1) {
+    if check_keyword(toks, "pub", 0) {
+        if check_keywords(toks, &["async", "const", "unsafe"], 1) {
+            return check_keyword(toks, "fn", 2);
+        } else if check_keyword(toks, "fn", 1) {
             return true;
         }
     }
     false
 }
 
+/// check if the token at the step position is the identifier
+/// specified, the match is exact so `publish` is not `pub`.
 pub fn check_identifier(toks: &KTokenStream, ident: &str, step: usize) -> bool {
-    let tok = toks.lookup(step);
-    if let TokenTree::Ident(val) = tok {
-        if val.to_string().contains(ident) {
-            return true;
-        }
-    }
-    false
+    check_identifiers(toks, &[ident], step)
 }
 
-pub fn check_tok(toks: &KTokenStream, ident: &str, step: usize) -> bool {
-    let tok = toks.lookup(step);
-    tok.to_string().contains(ident)
+/// check if the token at the step position is the keyword
+/// specified, a raw identifier like `r#type` is not a keyword.
+pub fn check_keyword(toks: &KTokenStream, keyword: &str, step: usize) -> bool {
+    toks.has(step) && is_keyword_tok(toks.lookup(step), keyword)
+}
+
+/// check if the token at the step position is one of the
+/// keywords specified.
+pub fn check_keywords(toks: &KTokenStream, keywords: &[&str], step: usize) -> bool {
+    keywords
+        .iter()
+        .any(|keyword| check_keyword(toks, keyword, step))
+}
+
+/// check if the token at the step position is exactly
+/// the one specified.
+pub fn check_tok(toks: &KTokenStream, tok: &str, step: usize) -> bool {
+    toks.has(step) && toks.lookup(step).match_tok(tok)
 }
 
 pub fn check_identifiers(toks: &KTokenStream, ident: &[&str], step: usize) -> bool {
+    if !toks.has(step) {
+        return false;
+    }
     let tok = toks.lookup(step);
     if let TokenTree::Ident(val) = tok {
         if ident.contains(&val.to_string().as_str()) {
@@ -267,9 +517,18 @@ pub fn check_identifiers(toks: &KTokenStream, ident: &[&str], step: usize) -> bo
     false
 }
 
-pub fn check_raw_toks(toks: &KTokenStream, ident: &[&str], step: usize) -> bool {
-    let tok = toks.lookup(step);
-    ident.contains(&tok.to_string().as_str())
+/// check if the token at the step position is the
+/// path separator `::`
+pub fn check_path_separator(toks: &KTokenStream, step: usize) -> bool {
+    if !toks.has(step + 1) {
+        return false;
+    }
+    match toks.lookup(step) {
+        TokenTree::Punct(punct) if punct.as_char() == ':' && punct.spacing() == Spacing::Joint => {
+            toks.lookup(step + 1).match_tok(":")
+        }
+        _ => false,
+    }
 }
 
 pub fn check_and_parse_return_type(
@@ -281,10 +540,18 @@ pub fn check_and_parse_return_type(
     }
     if check_tok(toks, "-", 0) {
         toks.next();
-        trace!(tracer, "ok parsed the `-`, now the next is {}", toks.peek());
+        trace!(
+            tracer,
+            "ok parsed the `-`, now the next is {:?}",
+            toks.try_peek()?
+        );
         if check_tok(toks, ">", 0) {
             toks.next();
-            trace!(tracer, "found the `>` no the next is {:?}", toks.peek());
+            trace!(
+                tracer,
+                "found the `>` no the next is {:?}",
+                toks.try_peek()?
+            );
             // FIXME: add a method to consube by steps
             let ty = parse_ty(toks, tracer)?;
             return Ok(ty);
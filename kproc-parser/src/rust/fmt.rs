@@ -1,58 +1,236 @@
 //! formatting module that contains the basic
 //! fmt function that convert in a string
 //! part of the rust syntax.
-use super::ast_nodes::{GenericParams, TyToken};
+use super::ast_nodes::{
+    Bound, GenericArg, GenericParam, GenericParams, LifetimeParam, TyKind, TyToken, WhereClause,
+    WherePredicate,
+};
 use crate::warn;
 
 pub(crate) fn fmt_generics(generics: &GenericParams) -> String {
     if generics.params.is_empty() {
         return String::new();
     }
-    let mut buff = "<".to_owned();
-    for generic in &generics.params {
-        buff += &format!("{generic}");
+    let params = generics
+        .params
+        .iter()
+        .map(|generic| generic.to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+    format!("<{params}>")
+}
+
+/// format the generics for an impl block, so the bounds are
+/// kept but the defaults are removed.
+pub(crate) fn fmt_impl_generics(generics: &GenericParams) -> String {
+    if generics.params.is_empty() {
+        return String::new();
     }
+    let params = generics
+        .params
+        .iter()
+        .map(|generic| match generic {
+            GenericParam::TypeParam(param) => {
+                let mut code = param.identifier.to_string();
+                if !param.bounds.is_empty() {
+                    code += &format!(": {}", fmt_bounds(&param.bounds));
+                }
+                code
+            }
+            GenericParam::ConstParam(param) => {
+                format!("{} {}: {}", param.const_tok, param.identifier, param.ty)
+            }
+            _ => generic.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+    format!("<{params}>")
+}
 
-    buff += ">";
-    buff
+/// format the generics of a type, so only the
+/// names of the parameters are kept.
+pub(crate) fn fmt_ty_generics(generics: &GenericParams) -> String {
+    if generics.params.is_empty() {
+        return String::new();
+    }
+    let params = generics
+        .params
+        .iter()
+        .map(|generic| match generic {
+            GenericParam::LifetimeParam(param) => {
+                format!("'{}", param.lifetime_or_label)
+            }
+            GenericParam::TypeParam(param) => param.identifier.to_string(),
+            GenericParam::ConstParam(param) => param.identifier.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+    format!("<{params}>")
 }
 
 pub fn fmt_ty(ty: &TyToken) -> String {
-    let mut prefix = String::new();
-    if let Some(refer) = &ty.ref_tok {
-        prefix += &refer.to_string();
+    match &ty.kind {
+        TyKind::ReferenceType(inner) => {
+            let mut code = String::from("&");
+            if let Some(lifetime) = &ty.lifetime {
+                code += &format!("{lifetime} ");
+            }
+            if let Some(mut_tok) = &ty.mut_tok {
+                code += &format!("{mut_tok} ");
+            }
+            code + &fmt_ty(inner)
+        }
+        TyKind::RawPointerType { mutable, ty } => {
+            let qualifier = if *mutable { "mut" } else { "const" };
+            format!("*{qualifier} {ty}")
+        }
+        TyKind::NeverType => "!".to_owned(),
+        TyKind::InferredType => "_".to_owned(),
+        TyKind::TupleType(types) if types.len() == 1 => format!("({},)", types[0]),
+        TyKind::TupleType(types) => format!("({})", fmt_types(types)),
+        TyKind::Parenthesized(ty) => format!("({ty})"),
+        TyKind::ArrayType { ty, len } => format!("[{ty}; {len}]"),
+        TyKind::SliceType(ty) => format!("[{ty}]"),
+        TyKind::ImplTrait => format!("impl {}", fmt_trait_path(ty)),
+        TyKind::TraitObject => match &ty.dyn_tok {
+            Some(dyn_tok) => format!("{dyn_tok} {}", fmt_trait_path(ty)),
+            None => fmt_trait_path(ty),
+        },
+        TyKind::TypePath => {
+            if ty.generics.is_none() {
+                let ident = ty.identifier.clone();
+                warn!(
+                    ["Vec"].contains(&ident.to_string().as_str()),
+                    ident, "the token required generics"
+                );
+            }
+            fmt_path(ty)
+        }
+        TyKind::QualifiedPathInType {
+            self_ty,
+            trait_ty,
+            segments,
+        } => {
+            let mut code = match trait_ty {
+                Some(trait_ty) => format!("<{self_ty} as {trait_ty}>"),
+                None => format!("<{self_ty}>"),
+            };
+            for segment in segments {
+                code += &format!("::{segment}");
+            }
+            code
+        }
+        TyKind::BareFunctionType(bare_fn) => {
+            let mut code = String::new();
+            if !bare_fn.for_lifetimes.is_empty() {
+                code += &format!("for<{}> ", fmt_lifetimes(&bare_fn.for_lifetimes));
+            }
+            for qualifier in &bare_fn.qualifiers {
+                code += &format!("{qualifier} ");
+            }
+            let inputs = bare_fn
+                .inputs
+                .iter()
+                .map(|(name, ty)| match name {
+                    Some(name) => format!("{name}: {ty}"),
+                    None => ty.to_string(),
+                })
+                .collect::<Vec<String>>()
+                .join(", ");
+            code += &format!("fn({inputs})");
+            if let Some(output) = &bare_fn.output {
+                code += &format!(" -> {output}");
+            }
+            code
+        }
+        TyKind::MacroInvocation { path, tokens } => {
+            let path = path.iter().map(|tok| tok.to_string()).collect::<String>();
+            format!("{path}!{tokens}")
+        }
     }
+}
 
-    if let Some(mut_tok) = &ty.mut_tok {
-        prefix += &mut_tok.to_string();
+fn fmt_path(ty: &TyToken) -> String {
+    if ty.segments.is_empty() {
+        let mut code = ty.identifier.to_string();
+        if let Some(generics) = &ty.generics {
+            code += &fmt_generic_args(generics);
+        }
+        return code;
     }
-
-    if let Some(dyn_tok) = &ty.dyn_tok {
-        prefix += &dyn_tok.to_string();
+    let path = ty
+        .segments
+        .iter()
+        .map(|segment| segment.to_string())
+        .collect::<Vec<String>>()
+        .join("::");
+    match ty.leading_colon {
+        Some(_) => format!("::{path}"),
+        None => path,
     }
+}
 
-    let mut postfix = String::new();
+fn fmt_trait_path(ty: &TyToken) -> String {
+    fmt_bounds(&ty.bounds)
+}
 
-    // FIXME: the lifetime possible here is only one?
-    if let Some(lifetime) = &ty.lifetime {
-        postfix += &format!("'{lifetime}, ");
-    }
+fn fmt_types(types: &[TyToken]) -> String {
+    types
+        .iter()
+        .map(|ty| ty.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
 
-    if let Some(generics) = &ty.generics {
-        postfix += "<";
-        postfix += &generics
-            .iter()
-            .map(|it| it.to_string())
-            .collect::<Vec<String>>()
-            .join(",");
-        postfix += ">";
-    } else {
-        let ident = ty.identifier.clone();
-        warn!(
-            ["Vec"].contains(&ident.to_string().as_str()),
-            ident, "the token required generics"
-        );
-    }
+pub fn fmt_generic_args(generics: &[GenericArg]) -> String {
+    let args = generics
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+    format!("<{args}>")
+}
+
+pub fn fmt_lifetimes(lifetimes: &[LifetimeParam]) -> String {
+    lifetimes
+        .iter()
+        .map(|lifetime| lifetime.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+pub fn fmt_bounds(bounds: &[Bound]) -> String {
+    bounds
+        .iter()
+        .map(|bound| bound.to_string())
+        .collect::<Vec<String>>()
+        .join(" + ")
+}
 
-    format!("{prefix} {}{postfix}", ty.identifier)
+pub fn fmt_where_clause(where_clause: &WhereClause) -> String {
+    let predicates = where_clause
+        .predicates
+        .iter()
+        .map(|predicate| match predicate {
+            WherePredicate::Lifetime(lifetime) => format!(
+                "'{}: {}",
+                lifetime.lifetime_or_label,
+                fmt_bounds(&lifetime.bounds)
+            ),
+            WherePredicate::Type(predicate) => {
+                let mut code = String::new();
+                if !predicate.for_lifetimes.is_empty() {
+                    code += &format!("for<{}> ", fmt_lifetimes(&predicate.for_lifetimes));
+                }
+                code += &format!(
+                    "{}: {}",
+                    predicate.bounded_ty,
+                    fmt_bounds(&predicate.bounds)
+                );
+                code
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+    format!("where {predicates}")
 }
@@ -0,0 +1,189 @@
+//! API to parse the associated items declared inside
+//! a rust `impl` or `trait` block.
+use crate::kparser::{self, KParserError, KParserTracer};
+use crate::kproc_macros::KTokenStream;
+use crate::proc_macro::{Delimiter, TokenStream, TokenTree};
+use crate::rust::ast_nodes::{
+    AssociatedConstToken, AssociatedItem, AssociatedTypeToken, MacroInvocationToken,
+};
+use crate::rust::core::*;
+use crate::rust::kattr::prelude::*;
+use crate::rust::kfunc::parse_fn;
+use crate::rust::ty::parse_ty;
+use crate::{build_error, parse_attributes, parse_visibility, trace};
+
+/// parse all the associated items inside the block of an
+/// `impl` or a `trait` until the end of the stream.
+///
+/// AssociatedItem :
+///    OuterAttribute* (
+///        MacroInvocationSemi
+///        | ( Visibility? ( TypeAlias | ConstantItem | Function ) )
+///    )
+pub fn parse_associated_items(
+    block: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<Vec<AssociatedItem>> {
+    let mut items = Vec::new();
+    while !block.is_end() {
+        // look ahead the token that identify the kind of the item,
+        // without consuming the attributes and the visibility.
+        let mut lookahead = block.fork();
+        parse_attributes!(&mut lookahead, tracer)?;
+        parse_visibility!(&mut lookahead);
+        let item = if check_keyword(&lookahead, "type", 0) {
+            AssociatedItem::AssociatedType(parse_associated_type(block, tracer)?)
+        } else if check_keyword(&lookahead, "const", 0)
+            && !check_keywords(&lookahead, &["fn", "async", "unsafe", "extern"], 1)
+        {
+            AssociatedItem::AssociatedConst(parse_associated_const(block, tracer)?)
+        } else if is_macro_invocation(&lookahead, 0) {
+            AssociatedItem::MacroInvocation(parse_macro_invocation(block, tracer)?)
+        } else {
+            let method = parse_fn(block, tracer)?;
+            if method.is_method() {
+                AssociatedItem::AssociatedMethod(method)
+            } else {
+                AssociatedItem::AssociatedFn(method)
+            }
+        };
+        trace!(tracer, "associated item found: `{item}`");
+        items.push(item);
+    }
+    Ok(items)
+}
+
+fn is_macro_invocation(stream: &KTokenStream, pos: usize) -> bool {
+    let mut pos = pos;
+    while stream.has(pos + 1) {
+        match stream.lookup(pos) {
+            TokenTree::Ident(_) => pos += 1,
+            TokenTree::Punct(punct) if punct.as_char() == ':' => pos += 1,
+            TokenTree::Punct(punct) => return punct.as_char() == '!',
+            TokenTree::Group(_) | TokenTree::Literal(_) => return false,
+        }
+    }
+    false
+}
+
+/// parse an associated constant like `const N: usize = 3;`
+///
+/// ConstantItem :
+///    const ( IDENTIFIER | _ ) : Type ( = Expression )? ;
+pub fn parse_associated_const(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<AssociatedConstToken> {
+    let attrs = parse_attributes!(stream, tracer)?;
+    let visibility = parse_visibility!(stream);
+    stream.expect_tok("const")?;
+    let ident = stream.expect_ident()?;
+    let separator = stream.expect_punct(':')?;
+    let ty = parse_ty(stream, tracer)?.ok_or(build_error!(
+        separator,
+        "failing to parse the type of the constant, this is a bug, please report it"
+    ))?;
+    let value = if stream.match_tok("=") {
+        stream.next(); // consume `=`
+        let mut expr = Vec::new();
+        while !stream.is_end() && !stream.match_tok(";") {
+            expr.push(stream.try_advance()?);
+        }
+        Some(TokenStream::from_iter(expr))
+    } else {
+        None
+    };
+    stream.expect_punct(';')?;
+    trace!(tracer, "associated const `{ident}: {ty}`");
+    Ok(AssociatedConstToken {
+        attrs,
+        visibility,
+        ident,
+        ty,
+        value,
+    })
+}
+
+/// parse an associated type like `type Item: Clone = u8;`
+///
+/// TypeAlias :
+///    type IDENTIFIER GenericParams? ( : TypeParamBounds )?
+///        WhereClause? ( = Type WhereClause?)? ;
+pub fn parse_associated_type(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<AssociatedTypeToken> {
+    let attrs = parse_attributes!(stream, tracer)?;
+    let visibility = parse_visibility!(stream);
+    stream.expect_tok("type")?;
+    let ident = stream.expect_ident()?;
+    let generics = check_and_parse_generics_params(stream, tracer)?;
+    let bounds = if stream.match_tok(":") {
+        stream.next(); // consume `:`
+        parse_type_param_bounds(stream, tracer)?
+    } else {
+        vec![]
+    };
+    let where_clause = check_and_parse_where_clause(stream, tracer)?;
+    let mut default_where_clause = None;
+    let default = if stream.match_tok("=") {
+        let eq_tok = stream.try_advance()?;
+        let ty = parse_ty(stream, tracer)?.ok_or(build_error!(
+            eq_tok,
+            "failing to parse the type of the associated type, this is a bug, please report it"
+        ))?;
+        default_where_clause = check_and_parse_where_clause(stream, tracer)?;
+        Some(ty)
+    } else {
+        None
+    };
+    stream.expect_punct(';')?;
+    trace!(tracer, "associated type `{ident}`");
+    Ok(AssociatedTypeToken {
+        attrs,
+        visibility,
+        ident,
+        generics,
+        bounds,
+        where_clause,
+        default,
+        default_where_clause,
+    })
+}
+
+/// parse a macro invocation inside a block like `my_macro!(...);`
+///
+/// MacroInvocationSemi :
+///    SimplePath ! ( TokenTree* ) ;
+///    | SimplePath ! [ TokenTree* ] ;
+///    | SimplePath ! { TokenTree* }
+pub fn parse_macro_invocation(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<MacroInvocationToken> {
+    let attrs = parse_attributes!(stream, tracer)?;
+    let mut path = Vec::new();
+    while !stream.match_tok("!") {
+        path.push(stream.try_advance()?);
+    }
+    stream.expect_punct('!')?;
+    if !stream.is_group() {
+        return Err(build_error!(
+            stream.try_peek()?.clone(),
+            "expected a delimited token tree after the macro name"
+        ));
+    }
+    let tokens = stream.try_advance()?;
+    let is_brace =
+        matches!(&tokens, TokenTree::Group(group) if group.delimiter() == Delimiter::Brace);
+    // the `;` is optional only when the macro is invoked with the `{}`
+    if !is_brace || (!stream.is_end() && stream.match_tok(";")) {
+        stream.expect_punct(';')?;
+    }
+    trace!(tracer, "macro invocation with tokens `{tokens}`");
+    Ok(MacroInvocationToken {
+        attrs,
+        path,
+        tokens,
+    })
+}
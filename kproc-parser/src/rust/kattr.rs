@@ -1,13 +1,16 @@
-use std::collections::HashMap;
+use std::rc::Rc;
 
 use crate::kparser::{KParserError, KParserTracer};
-use crate::kproc_macros::KTokenStream;
-use crate::proc_macro::TokenTree;
+use crate::kproc_macros::{KTokenStream, MatchTok};
+use crate::proc_macro::{Spacing, TokenStream, TokenTree};
+#[allow(deprecated)]
 use crate::rust::ast_nodes::{AttrToken, AttributeToken, CondAttributeToken};
+use crate::trace;
 use crate::{build_error, kparser};
-use crate::{check, trace};
 
-use super::ast_nodes::{Attr, AttributeV2Token};
+use super::ast_nodes::{Attr, AttributeV2Token, Attributes, Meta};
+use super::core::check_path_separator;
+use super::ordered_map::OrderedMap;
 
 pub mod macros {
     #[macro_export]
@@ -21,18 +24,20 @@ pub mod macros {
 }
 
 pub mod prelude {
+    pub use super::check_and_parse_inner_attributes;
     pub use super::check_and_parser_attributes_v2;
     pub use super::macros::*;
 }
 
 #[deprecated(note = "Pleas use the check_and_parse_cond_attribute! macro")]
+#[allow(deprecated)]
 pub fn check_and_parse_cond_attribute(
     ast: &mut KTokenStream,
     tracer: &dyn KParserTracer,
-) -> HashMap<String, AttrToken> {
+) -> OrderedMap<String, AttrToken> {
     tracer.log("check and parse an attribute");
     tracer.log(format!("{:?}", ast.peek()).as_str());
-    let mut attrs = HashMap::new();
+    let mut attrs = OrderedMap::new();
     if ast.match_tok("#") {
         let _ = ast.advance();
         tracer.log(format!("{:?}", ast.peek()).as_str());
@@ -69,6 +74,7 @@ pub fn check_and_parse_cond_attribute(
 }
 
 #[deprecated(note = "Pleas use the check_and_parse_cond_attribute! macro")]
+#[allow(deprecated)]
 pub fn check_and_parse_attribute(ast: &mut KTokenStream) -> Option<AttributeToken> {
     let name = ast.advance();
     // FIXME: check if it is a valid name
@@ -86,21 +92,39 @@ pub fn check_and_parse_attribute(ast: &mut KTokenStream) -> Option<AttributeToke
 pub fn check_and_parser_attributes_v2<T: KParserTracer + ?Sized>(
     stream: &mut KTokenStream,
     tracer: &T,
-) -> kparser::Result<HashMap<String, AttributeV2Token>> {
+) -> kparser::Result<Attributes> {
     trace!(tracer, "checking and parsing attributes");
 
-    let mut attrs = HashMap::new();
+    let mut attrs = Attributes::new();
     // Parsing case where there are multiple attributes on one fields
     while stream.match_tok("#") {
-        check!("#", stream.advance())?;
+        stream.expect_punct('#')?;
         let inner_attr = stream.match_tok("!").then(|| stream.next());
-        let (identifier, attr) = check_and_parse_attribute_v2(stream, tracer)?;
+        let (_, attr) = check_and_parse_attribute_v2(stream, tracer)?;
         let attr = if inner_attr.is_some() {
             AttributeV2Token::InnerAttribute(attr)
         } else {
             AttributeV2Token::OuterAttribute(attr)
         };
-        attrs.insert(identifier, attr);
+        attrs.push(attr);
+    }
+    Ok(attrs)
+}
+
+/// parse the inner attributes `#![...]` at the beginning of a
+/// block, without consuming the outer attributes of the first item.
+pub fn check_and_parse_inner_attributes<T: KParserTracer + ?Sized>(
+    stream: &mut KTokenStream,
+    tracer: &T,
+) -> kparser::Result<Attributes> {
+    trace!(tracer, "checking and parsing inner attributes");
+
+    let mut attrs = Attributes::new();
+    while stream.has(1) && stream.match_tok("#") && stream.lookup(1).match_tok("!") {
+        stream.expect_punct('#')?;
+        stream.expect_punct('!')?;
+        let (_, attr) = check_and_parse_attribute_v2(stream, tracer)?;
+        attrs.push(AttributeV2Token::InnerAttribute(attr));
     }
     Ok(attrs)
 }
@@ -110,57 +134,167 @@ pub fn check_and_parse_attribute_v2<T: KParserTracer + ?Sized>(
     tracer: &T,
 ) -> kparser::Result<(String, Attr)> {
     // unwrap the group given by the `#[]`
-    let mut inner_stream = stream.to_ktoken_stream();
+    let raw_attr = stream.try_peek()?.clone();
+    let mut inner_stream = stream.expect_group()?;
     trace!(
         tracer,
         "Attribute parsing: inner stream `{:?}`",
         inner_stream
     );
-    let raw_attr = stream.advance();
-    let identifier = inner_stream.advance();
-    if inner_stream.is_end() {
-        return Ok((
-            identifier.to_string(),
-            Attr {
-                path: Vec::new(),
-                identifier,
-                value: None,
-                raw_attr,
-            },
+    let meta = parse_meta_item(&mut inner_stream, tracer)?;
+    if !inner_stream.is_end() {
+        return Err(build_error!(
+            inner_stream.peek().clone(),
+            "Error while parsing an attribute, token `{}` not expected",
+            inner_stream.peek()
         ));
     }
-    let (_, value) = match inner_stream.peek() {
-        TokenTree::Group(_) => check_and_parse_attribute_v2(&mut inner_stream, tracer)?,
-        TokenTree::Punct(punt) => {
-            trace!(tracer, "Attribute parsing: separator `{punt}`");
-            inner_stream.next();
-            let value = inner_stream.advance();
-            (
-                String::new(),
-                Attr {
-                    path: Vec::new(),
-                    identifier: value.clone(),
-                    value: None,
-                    raw_attr: value,
-                },
-            )
-        }
-        // FIXME in case of multiple attribute this will panic
-        _ => {
-            return Err(build_error!(
-                inner_stream.peek().clone(),
-                "Error while parsing an attribute, token `{}` not expected",
-                inner_stream.peek()
-            ))
-        }
-    };
+    let identifier = meta
+        .name()
+        .cloned()
+        .ok_or_else(|| build_error!(raw_attr.clone(), "expected the path of the attribute"))?;
     Ok((
         identifier.to_string(),
         Attr {
-            path: Vec::new(),
+            path: meta.path().to_vec(),
             identifier,
-            value: Some(value.into()),
+            value: legacy_value(&meta),
             raw_attr,
+            meta,
         },
     ))
 }
+
+/// parse one of the items inside an attribute list, where
+/// the tokens that are not a meta until the next `,` are kept
+/// as `Meta::Verbatim`, like `?Send` in `#[async_trait(?Send)]`.
+pub fn parse_meta<T: KParserTracer + ?Sized>(
+    stream: &mut KTokenStream,
+    tracer: &T,
+) -> kparser::Result<Meta> {
+    let start = stream.checkpoint();
+    if let Ok(meta) = parse_meta_item(stream, tracer) {
+        if stream.is_end() || stream.match_tok(",") {
+            return Ok(meta);
+        }
+    }
+    stream.rewind(start);
+    let mut tokens = Vec::new();
+    while !stream.is_end() && !stream.match_tok(",") {
+        tokens.push(stream.try_advance()?);
+    }
+    let tokens = TokenStream::from_iter(tokens);
+    trace!(tracer, "Attribute parsing: verbatim meta `{tokens}`");
+    Ok(Meta::Verbatim(tokens))
+}
+
+/// parse the content of an attribute, or one of
+/// the items inside a list.
+///
+/// Meta :
+///   SimplePath
+/// | SimplePath = Expression
+/// | SimplePath ( (Meta (, Meta)* ,?)? )
+/// | Literal
+fn parse_meta_item<T: KParserTracer + ?Sized>(
+    stream: &mut KTokenStream,
+    tracer: &T,
+) -> kparser::Result<Meta> {
+    if let TokenTree::Literal(_) = stream.try_peek()? {
+        return Ok(Meta::Lit(stream.try_advance()?));
+    }
+    let (path, name) = parse_simple_path(stream)?;
+    trace!(
+        tracer,
+        "Attribute parsing: meta `{name}` with path `{:?}`",
+        path
+    );
+    if stream.is_end() || stream.match_tok(",") {
+        return Ok(Meta::Path { path, name });
+    }
+    match stream.peek() {
+        TokenTree::Group(_) => {
+            let mut nested_stream = stream.expect_group()?;
+            let nested = parse_nested_meta(&mut nested_stream, tracer)?;
+            Ok(Meta::List { path, name, nested })
+        }
+        // a joint `=` is the start of `=>` or `==`
+        TokenTree::Punct(punct) if punct.as_char() == '=' && punct.spacing() == Spacing::Alone => {
+            let eq_tok = stream.try_advance()?;
+            let mut value = Vec::new();
+            while !stream.is_end() && !stream.match_tok(",") {
+                value.push(stream.try_advance()?);
+            }
+            if value.is_empty() {
+                return Err(build_error!(eq_tok, "expected a value after `=`"));
+            }
+            Ok(Meta::NameValue {
+                path,
+                name,
+                value: TokenStream::from_iter(value),
+            })
+        }
+        _ => Err(build_error!(
+            stream.peek().clone(),
+            "Error while parsing an attribute, token `{}` not expected",
+            stream.peek()
+        )),
+    }
+}
+
+/// parse a simple path like `serde::rename` and return
+/// the segments before the last one, and the last one.
+///
+/// SimplePath :
+///    ::? SimplePathSegment (:: SimplePathSegment)*
+fn parse_simple_path(stream: &mut KTokenStream) -> kparser::Result<(Vec<TokenTree>, TokenTree)> {
+    if check_path_separator(stream, 0) {
+        stream.next();
+        stream.next();
+    }
+    let mut path = Vec::new();
+    let mut name = stream.expect_ident()?;
+    while check_path_separator(stream, 0) {
+        stream.next();
+        stream.next();
+        path.push(name);
+        name = stream.expect_ident()?;
+    }
+    Ok((path, name))
+}
+
+/// parse the comma separated items inside an attribute list.
+pub fn parse_nested_meta<T: KParserTracer + ?Sized>(
+    stream: &mut KTokenStream,
+    tracer: &T,
+) -> kparser::Result<Vec<Meta>> {
+    let mut nested = Vec::new();
+    while !stream.is_end() {
+        nested.push(parse_meta(stream, tracer)?);
+        if !stream.is_end() {
+            stream.expect_punct(',')?;
+        }
+    }
+    Ok(nested)
+}
+
+/// build the `Attr::value` from the meta, where only
+/// the first item of a list is kept.
+fn legacy_value(meta: &Meta) -> Option<Rc<Attr>> {
+    let value = match meta {
+        Meta::Path { .. } | Meta::Lit(_) | Meta::Verbatim(_) => return None,
+        Meta::NameValue { value, .. } => {
+            let value = value.clone().into_iter().next()?;
+            Meta::Lit(value)
+        }
+        Meta::List { nested, .. } => nested.first()?.clone(),
+    };
+    let identifier = value.name()?.clone();
+    Some(Rc::new(Attr {
+        path: value.path().to_vec(),
+        identifier: identifier.clone(),
+        value: legacy_value(&value),
+        raw_attr: identifier,
+        meta: value,
+    }))
+}
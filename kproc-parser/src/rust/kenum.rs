@@ -1,45 +1,86 @@
 // WIP: parsing enum code
-use std::collections::HashMap;
 use std::vec::Vec;
 
 use crate::kparser::{KParserError, KParserTracer};
-use crate::kproc_macros::KTokenStream;
+use crate::kproc_macros::{KTokenStream, MatchTok};
 use crate::proc_macro::{Delimiter, TokenStream, TokenTree};
 use crate::{build_error, check, kparser, parse_attributes, parse_visibility, trace};
 
-use super::ast_nodes::AttributeV2Token;
+use super::ast_nodes::{
+    Attributes, FieldToken, GenericParams, ImplGenerics, TypeGenerics, Visibility, WhereClause,
+};
+use super::core::{check_and_parse_generics_params, check_and_parse_where_clause};
 use super::kattr::prelude::*;
+use super::keywords::is_keyword;
+use super::kstruct::{parse_struct_fields, parse_tuple_fields};
+use super::ordered_map::OrderedMap;
 
 #[derive(Debug)]
 pub struct EnumToken {
-    pub attributes: HashMap<String, AttributeV2Token>,
-    pub visibility: Option<TokenTree>,
+    pub attributes: Attributes,
+    pub visibility: Visibility,
     pub identifier: TokenTree,
+    pub generics: Option<GenericParams>,
+    pub where_clause: Option<WhereClause>,
     pub raw_body: TokenStream,
     pub values: Vec<EnumValue>,
 }
 
+impl EnumToken {
+    /// split the generics of the enum in the parts that are
+    /// needed to generate an impl block for the enum.
+    pub fn split_for_impl(&self) -> (ImplGenerics<'_>, TypeGenerics<'_>, Option<&WhereClause>) {
+        match &self.generics {
+            Some(generics) => generics.split_for_impl(self.where_clause.as_ref()),
+            None => (
+                ImplGenerics::default(),
+                TypeGenerics::default(),
+                self.where_clause.as_ref(),
+            ),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct EnumValue {
-    pub attributes: HashMap<String, AttributeV2Token>,
+    pub attributes: Attributes,
     pub kind: EnumValueKind,
     pub identifier: TokenTree,
+    /// The explicit discriminant of the variant,
+    /// like `1` in `A = 1`.
+    pub discriminant: Option<TokenStream>,
+}
+
+impl EnumValue {
+    /// return the doc comments of the variant, see `Attributes::docs`.
+    pub fn docs(&self) -> Option<String> {
+        self.attributes.docs()
+    }
 }
 
 #[derive(Debug)]
 pub enum EnumValueKind {
-    Named(HashMap<String, TokenTree>),
-    Anonymus(Vec<TokenTree>),
+    Named(OrderedMap<String, FieldToken>),
+    /// The fields of a tuple variant, where the
+    /// identifier of each field is the index literal.
+    Anonymus(Vec<FieldToken>),
     Simple,
 }
 
 impl std::fmt::Display for EnumToken {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if let Some(ref vis) = self.visibility {
-            write!(f, "{vis}")?;
+        if !self.visibility.is_inherited() {
+            write!(f, "{} ", self.visibility)?;
         }
         let identifier = &self.identifier;
-        writeln!(f, " enum {identifier} {{")?;
+        write!(f, "enum {identifier}")?;
+        if let Some(ref generics) = self.generics {
+            write!(f, "{generics}")?;
+        }
+        if let Some(ref where_clause) = self.where_clause {
+            write!(f, " {where_clause}")?;
+        }
+        writeln!(f, " {{")?;
         writeln!(f, "{}", self.raw_body)?;
         writeln!(f, "}}")
     }
@@ -54,97 +95,129 @@ impl Default for EnumToken {
 pub fn parse(stream: &mut KTokenStream, tracer: &dyn KParserTracer) -> kparser::Result<EnumToken> {
     let attributes = parse_attributes!(stream, tracer)?;
     let visibility = parse_visibility!(stream);
-    check!("enum", stream.advance())?;
-    let identifier = stream.advance();
-    let raw_body = stream.unwrap_group_as_stream();
-    let mut body_stream = KTokenStream::new(&raw_body);
+    stream.expect_tok("enum")?;
+    let identifier = stream.expect_ident()?;
+    let generics = check_and_parse_generics_params(stream, tracer)?;
+    let where_clause = check_and_parse_where_clause(stream, tracer)?;
+    let mut body_stream = stream.expect_group()?;
+    let raw_body = body_stream.to_stream();
     let values = parse_body(&mut body_stream, tracer)?;
     Ok(EnumToken {
         attributes,
         visibility,
         identifier,
+        generics,
+        where_clause,
         raw_body,
         values,
     })
 }
 
-fn parse_body<T: KParserTracer + ?Sized>(
+fn parse_body(
     stream: &mut KTokenStream,
-    tracer: &T,
+    tracer: &dyn KParserTracer,
 ) -> kparser::Result<Vec<EnumValue>> {
     let mut values = Vec::new();
     while !stream.is_end() {
         let attributes = parse_attributes!(stream, tracer)?;
-        let identifier = stream.advance();
+        // the visibility is not allowed by the compiler
+        // but it is allowed by the syntax.
+        let _ = parse_visibility!(stream);
+        let identifier = stream.expect_ident()?;
         trace!(tracer, "identifier {:?}", identifier);
-        let content = stream.peek();
-        let kind = match stream.peek() {
-            TokenTree::Group(ref group) => match group.delimiter() {
-                Delimiter::Brace => {
-                    let group = stream.unwrap_group_as_stream();
-                    stream.next();
-                    parse_named_value(group, tracer)?
+        let kind = if stream.is_end() {
+            EnumValueKind::Simple
+        } else {
+            let content = stream.peek().clone();
+            match content {
+                TokenTree::Group(ref group) => match group.delimiter() {
+                    Delimiter::Brace => {
+                        let mut group = stream.expect_group()?;
+                        parse_named_value(&mut group, tracer)?
+                    }
+                    Delimiter::Parenthesis => {
+                        let mut group = stream.expect_group()?;
+                        EnumValueKind::Anonymus(parse_tuple_fields(&mut group, tracer)?)
+                    }
+                    _ => return Err(build_error!(content, "invalid token inside enum")),
+                },
+                TokenTree::Punct(_) => EnumValueKind::Simple,
+                _ => {
+                    return Err(build_error!(
+                        content.clone(),
+                        "token `{}` not expected",
+                        content
+                    ))
                 }
-                Delimiter::Parenthesis => {
-                    let group = stream.unwrap_group_as_stream();
-                    stream.next();
-                    parse_anonymus_value(group, tracer)?
-                }
-                _ => return Err(build_error!(content.clone(), "invalid token inside enum")),
-            },
-            TokenTree::Punct(_) => EnumValueKind::Simple,
-            _ => {
-                return Err(build_error!(
-                    content.clone(),
-                    "token `{}` not expected",
-                    content
-                ))
             }
         };
+        let discriminant = check_and_parse_discriminant(stream)?;
         if !stream.is_end() {
-            check!(",", stream.advance())?;
+            check!(",", stream.try_advance()?)?;
         }
         trace!(tracer, "Enum kind found {:?}", kind);
         values.push(EnumValue {
             attributes,
             kind,
             identifier,
+            discriminant,
         });
     }
     Ok(values)
 }
 
-fn parse_named_value<T: KParserTracer + ?Sized>(
-    stream: TokenStream,
-    _: &T,
+fn parse_named_value(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
 ) -> kparser::Result<EnumValueKind> {
-    let mut stream = KTokenStream::new(&stream);
-    let mut fields = HashMap::new();
-    while !stream.is_end() {
-        let identifier = stream.advance();
-        check!(":", stream.advance())?;
-        let ttype = stream.advance();
-        fields.insert(identifier.to_string(), ttype);
-        if !stream.is_end() {
-            check!(",", stream.advance())?;
-        }
-    }
+    let fields = parse_struct_fields(stream, tracer)?
+        .into_iter()
+        .map(|field| (field.identifier.to_string(), field))
+        .collect();
     Ok(EnumValueKind::Named(fields))
 }
 
-fn parse_anonymus_value<T: KParserTracer + ?Sized>(
-    stream: TokenStream,
-    tracer: &T,
-) -> kparser::Result<EnumValueKind> {
-    let mut stream = KTokenStream::new(&stream);
-    let mut fields = Vec::new();
-    while !stream.is_end() {
-        let ttype = stream.advance();
-        trace!(tracer, "ttype {:?}", ttype);
-        fields.push(ttype);
-        if !stream.is_end() {
-            check!(",", stream.advance())?;
+/// parse the discriminant of the variant if it is present,
+/// all the tokens until the `,` are the expression.
+///
+/// EnumItemDiscriminant :
+///    = Expression
+fn check_and_parse_discriminant(stream: &mut KTokenStream) -> kparser::Result<Option<TokenStream>> {
+    if stream.is_end() || !stream.match_tok("=") {
+        return Ok(None);
+    }
+    let eq_tok = stream.try_advance()?;
+    let mut expr = Vec::new();
+    while let Some(tok) = stream.next_if(|tok| !tok.match_tok(",")) {
+        // two operands are never one after the other inside an
+        // expression, so this is a variant with a missing `,`.
+        if expr.last().is_some_and(is_operand)
+            && is_operand(&tok)
+            && !matches!(tok, TokenTree::Group(_))
+        {
+            return Err(build_error!(
+                tok.clone(),
+                "expected `,` before `{tok}`, the discriminant is not terminated"
+            ));
         }
+        expr.push(tok);
+    }
+    if expr.is_empty() {
+        return Err(build_error!(
+            eq_tok,
+            "expected an expression after `=` in the discriminant"
+        ));
+    }
+    Ok(Some(TokenStream::from_iter(expr)))
+}
+
+/// check if the token can be an operand of an expression, so
+/// an identifier that is not a keyword like `as`, a literal
+/// or a group.
+fn is_operand(tok: &TokenTree) -> bool {
+    match tok {
+        TokenTree::Ident(ident) => !is_keyword(&ident.to_string()),
+        TokenTree::Literal(_) | TokenTree::Group(_) => true,
+        TokenTree::Punct(_) => false,
     }
-    Ok(EnumValueKind::Anonymus(fields))
 }
@@ -0,0 +1,99 @@
+//! Keyword table of the rust language used to match
+//! the identifiers exactly.
+//!
+//! See <https://doc.rust-lang.org/reference/keywords.html>
+use std::fmt::{self, Write};
+
+use crate::proc_macro::TokenTree;
+
+/// Keywords that can be used only in their correct context.
+pub const STRICT_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+/// Keywords that are not used yet, but are reserved for
+/// future use.
+pub const RESERVED_KEYWORDS: &[&str] = &[
+    "abstract", "become", "box", "do", "final", "gen", "macro", "override", "priv", "try",
+    "typeof", "unsized", "virtual", "yield",
+];
+
+/// Keywords that have a special meaning only in certain
+/// contexts, like `union` or `macro_rules`.
+///
+/// The lifetime `'static` is not in the table because it
+/// is not a single identifier.
+pub const WEAK_KEYWORDS: &[&str] = &["macro_rules", "raw", "safe", "union"];
+
+pub fn is_strict_keyword(ident: &str) -> bool {
+    STRICT_KEYWORDS.contains(&ident)
+}
+
+pub fn is_reserved_keyword(ident: &str) -> bool {
+    RESERVED_KEYWORDS.contains(&ident)
+}
+
+pub fn is_weak_keyword(ident: &str) -> bool {
+    WEAK_KEYWORDS.contains(&ident)
+}
+
+/// check if the identifier is a keyword of any kind, a raw
+/// identifier like `r#type` is never a keyword.
+pub fn is_keyword(ident: &str) -> bool {
+    is_strict_keyword(ident) || is_reserved_keyword(ident) || is_weak_keyword(ident)
+}
+
+/// check if the token is an identifier equal to the keyword,
+/// so `publish` or `r#pub` are not matching `pub`.
+///
+/// Return always `false` if `keyword` is not a keyword.
+pub fn is_keyword_tok(tok: &TokenTree, keyword: &str) -> bool {
+    is_keyword(keyword) && is_ident_tok(tok, keyword)
+}
+
+/// check if the token is an identifier equal to `name`, without
+/// allocating the string of the identifier.
+pub fn is_ident_tok(tok: &TokenTree, name: &str) -> bool {
+    // `proc_macro::Ident` expose only `Display`, so the identifier
+    // is compared while it is written.
+    struct StripPrefix<'a>(&'a str);
+
+    impl Write for StripPrefix<'_> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.0 = self.0.strip_prefix(s).ok_or(fmt::Error)?;
+            Ok(())
+        }
+    }
+
+    match tok {
+        TokenTree::Ident(ident) => {
+            let mut rest = StripPrefix(name);
+            write!(rest, "{ident}").is_ok() && rest.0.is_empty()
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proc_macro::TokenStream;
+
+    fn first_tok(source: &str) -> TokenTree {
+        let stream: TokenStream = source.parse().unwrap();
+        stream.into_iter().next().unwrap()
+    }
+
+    #[test]
+    fn keyword_tok_matches_exactly() {
+        assert!(is_keyword_tok(&first_tok("pub"), "pub"));
+        assert!(!is_keyword_tok(&first_tok("publish"), "pub"));
+        assert!(!is_keyword_tok(&first_tok("pu"), "pub"));
+        assert!(!is_keyword_tok(&first_tok("r#pub"), "pub"));
+        assert!(!is_keyword_tok(&first_tok("foo"), "foo"));
+        assert!(is_ident_tok(&first_tok("_"), "_"));
+    }
+}
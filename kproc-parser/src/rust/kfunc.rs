@@ -1,15 +1,19 @@
 //! kfunc is the module that it is used to
 //! parse the function like rust syntax.
 use crate::kparser::{KParserError, KParserTracer};
-use crate::kproc_macros::KTokenStream;
+use crate::kproc_macros::{KTokenStream, MatchTok};
 use crate::proc_macro::TokenTree;
-use crate::rust::core::{check_and_parse_bounds, check_and_parse_return_type};
-use crate::rust::kattr::check_and_parse_cond_attribute;
+use crate::rust::core::{
+    check_and_parse_generics_params, check_and_parse_lifetime, check_and_parse_return_type,
+    check_and_parse_where_clause,
+};
+use crate::rust::kattr::prelude::*;
+use crate::rust::kpattern::parse_pattern;
 use crate::rust::ty::parse_ty;
-use crate::{build_error, check, parse_visibility, trace};
+use crate::{build_error, parse_attributes, parse_visibility, trace};
 
-use super::ast_nodes::{MethodDeclToken, TyToken};
-use super::core::{check_and_parse_fn_qualifier, check_is_fun_with_visibility};
+use super::ast_nodes::{FnParam, LifetimeParam, MethodDeclToken, Receiver, TyToken};
+use super::core::check_and_parse_fn_qualifiers;
 
 /// helper function to parse the method/function declaration
 ///
@@ -34,100 +38,204 @@ pub fn parse_fn(
 ) -> Result<MethodDeclToken, KParserError> {
     trace!(tracer, "Start parsing fn");
 
-    let attrs = check_and_parse_cond_attribute(toks, tracer);
-    let visibility = check_is_fun_with_visibility(toks).then(|| parse_visibility!(toks).unwrap());
-    let qualifier = check_and_parse_fn_qualifier(toks);
-    let fn_tok = toks.advance();
-    check!("fn", fn_tok)?;
+    let attrs = parse_attributes!(toks, tracer)?;
+    let visibility = parse_visibility!(toks);
+    let qualifiers = check_and_parse_fn_qualifiers(toks);
+    toks.expect_tok("fn")?;
 
-    let ident = toks.advance();
+    let ident = toks.expect_ident()?;
     trace!(
         tracer,
         "function name {ident} and next tok: {:?}",
-        toks.peek()
+        toks.try_peek()?
     );
-    let generics = check_and_parse_bounds(toks, tracer)?;
+    let generics = check_and_parse_generics_params(toks, tracer)?;
     trace!(tracer, "starting parsing fn params");
-    let raw_params = toks.unwrap_group_as_stream();
-    let mut params_stream: KTokenStream = raw_params.clone().into();
-    let params = parse_fn_params(&mut params_stream, tracer)?;
-    trace!(tracer, "fn parametes {:?}", params);
-    toks.next();
+    let mut params_stream = toks.expect_group()?;
+    let raw_params = params_stream.to_stream();
+    let receiver = check_and_parse_receiver(&mut params_stream, tracer)?;
+    let fn_params = parse_fn_inputs(&mut params_stream, tracer)?;
+    let params = simple_params(&fn_params);
+    trace!(tracer, "fn parametes {:?}", fn_params);
 
-    // FIXME: parse where clouse
     let rt_ty = check_and_parse_return_type(toks, tracer)?;
+    let where_clause = check_and_parse_where_clause(toks, tracer)?;
     trace!(
         tracer,
         "return type {:?} next should be the body function: {:?}",
         rt_ty,
-        toks.peek()
+        toks.try_peek()?
     );
 
     // The trait has a function declaration without
     // body.
     let body = if toks.is_group() {
-        let body = toks.unwrap_group_as_stream();
-        toks.next();
-        Some(body)
+        Some(toks.expect_group()?.to_stream())
     } else {
-        let toks = toks.advance();
-        check!(";", toks)?;
+        toks.expect_punct(';')?;
         None
     };
 
     let method = MethodDeclToken {
         attrs,
         visibility,
-        qualifier,
+        qualifiers,
         ident,
         generics,
         raw_params,
+        receiver,
         params,
+        fn_params,
         raw_body: body,
         return_ty: rt_ty,
+        where_clause,
     };
     Ok(method)
 }
 
+/// parse the function parameters and return only the parameters
+/// with a simple identifier pattern, in a tuple of
+/// `(identifier, Type Token)`.
 pub fn parse_fn_params(
     raw_params: &mut KTokenStream,
     tracer: &dyn KParserTracer,
 ) -> Result<Vec<(TokenTree, TyToken)>, KParserError> {
+    let fn_params = parse_fn_inputs(raw_params, tracer)?;
+    Ok(simple_params(&fn_params))
+}
+
+fn simple_params(fn_params: &[FnParam]) -> Vec<(TokenTree, TyToken)> {
+    fn_params
+        .iter()
+        .filter_map(|param| {
+            param
+                .pattern
+                .ident()
+                .map(|ident| (ident.clone(), param.ty.clone()))
+        })
+        .collect()
+}
+
+/// parse all the function parameters with the pattern
+/// and the type of each parameter.
+///
+/// FunctionParameters :
+///    SelfParam ,?
+///    | (SelfParam ,)? FunctionParam (, FunctionParam)* ,?
+pub fn parse_fn_inputs(
+    raw_params: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> Result<Vec<FnParam>, KParserError> {
     trace!(
         tracer,
         "parsing fn params from the following source: {:?}",
         raw_params
     );
     let mut params = Vec::new();
+    // the receiver is not a parameter, so we skip it
+    // if it is not already parsed.
+    let _ = check_and_parse_receiver(raw_params, tracer)?;
     // the stream of token that we get in
     // are the token that are inside a `(...)`
     // when in rust is a `TokenTree::Group` token
     while !raw_params.is_end() {
-        // FIXME here we need to check the special case of
-        // `&mut self`
-        if raw_params.match_tok("&") || raw_params.match_tok("self") | raw_params.match_tok("mut") {
-            while !raw_params.is_end() && !raw_params.match_tok(",") {
-                trace!(tracer, "`self` found `{:?}`", raw_params.advance());
-            }
-            if raw_params.is_end() {
-                trace!(tracer, "end of the params stream.");
-                break;
-            }
-            if raw_params.match_tok(",") {
-                check!(",", raw_params.advance())?;
-            }
-        }
-        let ident = raw_params.advance();
-        trace!(tracer, "parameters name `{ident}`");
-        check!(":", raw_params.advance())?;
+        let attrs = parse_attributes!(raw_params, tracer)?;
+        let pattern = parse_pattern(raw_params, tracer)?;
+        trace!(tracer, "parameters pattern `{pattern}`");
+        let separator = raw_params.expect_punct(':')?;
         let ty = parse_ty(raw_params, tracer)?.ok_or(build_error!(
-            ident.clone(),
+            separator,
             "fails to parse the rust type, this is a bug, please open a issue"
         ))?;
-        trace!(tracer, "param found `{ident}: {ty}`");
-        params.push((ident, ty));
+        trace!(tracer, "param found `{pattern}: {ty}`");
+        params.push(FnParam { attrs, pattern, ty });
         // keep going, or there are more token, or we finish the stream
         // but the while will check the last case.
     }
     Ok(params)
 }
+
+/// parse the `self` receiver of a method if it is present at
+/// the beginning of the parameters stream, and consume the `,`
+/// that divide the receiver from the other parameters.
+///
+/// SelfParam :
+///    OuterAttribute* ( ShorthandSelf | TypedSelf )
+pub fn check_and_parse_receiver(
+    raw_params: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> Result<Receiver, KParserError> {
+    if !is_receiver(raw_params) {
+        return Ok(Receiver::None);
+    }
+    let attrs = parse_attributes!(raw_params, tracer)?;
+    let receiver = if raw_params.match_tok("&") {
+        raw_params.next(); // consume `&`
+        let lifetime = check_and_parse_lifetime(raw_params).map(|lifetime| LifetimeParam {
+            lifetime_or_label: lifetime,
+            bounds: vec![],
+        });
+        if raw_params.match_tok("mut") {
+            raw_params.next(); // consume `mut`
+            let self_tok = raw_params.expect_tok("self")?;
+            Receiver::RefMut {
+                attrs,
+                self_tok,
+                lifetime,
+            }
+        } else {
+            let self_tok = raw_params.expect_tok("self")?;
+            Receiver::Ref {
+                attrs,
+                self_tok,
+                lifetime,
+            }
+        }
+    } else {
+        let mut_tok = raw_params.next_if(|tok| tok.match_tok("mut"));
+        let self_tok = raw_params.expect_tok("self")?;
+        if !raw_params.is_end() && raw_params.match_tok(":") {
+            let separator = raw_params.try_advance()?;
+            let ty = parse_ty(raw_params, tracer)?.ok_or(build_error!(
+                separator,
+                "fails to parse the type of `self`, this is a bug, please open a issue"
+            ))?;
+            Receiver::Typed {
+                attrs,
+                mut_tok,
+                self_tok,
+                ty: Box::new(ty),
+            }
+        } else if mut_tok.is_some() {
+            Receiver::MutValue { attrs, self_tok }
+        } else {
+            Receiver::Value { attrs, self_tok }
+        }
+    };
+    trace!(tracer, "receiver found `{receiver}`");
+    if !raw_params.is_end() && raw_params.match_tok(",") {
+        raw_params.expect_punct(',')?;
+    }
+    Ok(receiver)
+}
+
+/// look ahead the stream to check if the first parameter
+/// is a `self` receiver, without consuming it.
+fn is_receiver(raw_params: &KTokenStream) -> bool {
+    let mut pos = 0;
+    // skip the attributes `#[...]`
+    while raw_params.has(pos + 1) && raw_params.lookup(pos).match_tok("#") {
+        pos += 2;
+    }
+    if raw_params.has(pos) && raw_params.lookup(pos).match_tok("&") {
+        pos += 1;
+        // skip the lifetime `'a`
+        if raw_params.has(pos) && raw_params.lookup(pos).match_tok("'") {
+            pos += 2;
+        }
+    }
+    if raw_params.has(pos) && raw_params.lookup(pos).match_tok("mut") {
+        pos += 1;
+    }
+    raw_params.has(pos) && raw_params.lookup(pos).match_tok("self")
+}
@@ -1,54 +1,79 @@
 //! API to parse a rust `impl`
 use crate::kparser::{self, KParserError, KParserTracer};
-use crate::kproc_macros::KTokenStream;
+use crate::kproc_macros::{KTokenStream, MatchTok};
 use crate::rust::ast_nodes::ImplToken;
-use crate::rust::core::check_and_parse_bounds;
-use crate::rust::kattr::check_and_parse_cond_attribute;
-use crate::rust::kfunc::parse_fn;
-use crate::{check, trace};
+use crate::rust::core::{
+    check_and_parse_generics_params, check_and_parse_where_clause, check_keyword,
+};
+use crate::rust::kassoc::parse_associated_items;
+use crate::rust::kattr::prelude::*;
+use crate::rust::keywords::is_keyword_tok;
+use crate::rust::ty::parse_ty;
+use crate::{build_error, parse_attributes, trace};
 
 /// helper function that allow to parse an impl block
 pub fn parse_impl(
     toks: &mut KTokenStream,
     tracer: &dyn KParserTracer,
 ) -> kparser::Result<ImplToken> {
-    let attr = check_and_parse_cond_attribute(toks, tracer);
-    let impl_tok = toks.advance();
-    check!("impl", impl_tok)?;
-    let generics = check_and_parse_bounds(toks, tracer)?;
-    let name = toks.advance();
-    let _for_ty = if toks.match_tok("for") {
-        // FIXME: parsing the generic and lifetime usage
-        let for_tok = toks.advance();
-        check!("for", for_tok)?;
-        Some(toks.advance())
+    let attr = parse_attributes!(toks, tracer)?;
+    let unsafe_tok = toks.next_if(|tok| is_keyword_tok(tok, "unsafe"));
+    toks.expect_tok("impl")?;
+    let generics = check_and_parse_generics_params(toks, tracer)?;
+    let negative_tok = toks.next_if(|tok| tok.match_tok("!"));
+
+    let first_tok = toks.try_peek()?.clone();
+    let first_ty = parse_ty(toks, tracer)?.ok_or(build_error!(
+        first_tok.clone(),
+        "failing to parse the type of the impl block, this is a bug, please report it"
+    ))?;
+    // the first type is the trait only when it is followed
+    // by the `for` keyword, otherwise it is an inherent impl.
+    let (trait_ty, self_ty) = if check_keyword(toks, "for", 0) {
+        toks.expect_tok("for")?;
+        let self_tok = toks.try_peek()?.clone();
+        let self_ty = parse_ty(toks, tracer)?.ok_or(build_error!(
+            self_tok,
+            "failing to parse the `Self` type of the impl block, this is a bug, please report it"
+        ))?;
+        (Some(first_ty), self_ty)
     } else {
-        None
+        (None, first_ty)
     };
+    if negative_tok.is_some() && trait_ty.is_none() {
+        return Err(build_error!(
+            first_tok,
+            "negative impls are allowed only for trait impls"
+        ));
+    }
+    let name = self_ty.identifier.clone();
 
-    // FIXME: parse the where clause!
+    let where_clause = check_and_parse_where_clause(toks, tracer)?;
 
     // store the raw content of the block because there
     // if the user want parse it,
     // it has all the necessary tools for parse it.
-    let raw_impl_block = toks.unwrap_group_as_stream();
-    let mut impl_block = toks.to_ktoken_stream();
-
-    let mut funs = Vec::new();
-    while !impl_block.is_end() {
-        // FIXME we Suppose to have all the function and no
-        // extra stuff
-        let fn_tok = parse_fn(&mut impl_block, tracer)?;
-        funs.push(fn_tok);
-    }
+    let mut impl_block = toks.expect_group()?;
+    let raw_impl_block = impl_block.to_stream();
+    let inn_attrs = check_and_parse_inner_attributes(&mut impl_block, tracer)?;
+    let associated_items = parse_associated_items(&mut impl_block, tracer)?;
+    let funs = associated_items
+        .iter()
+        .filter_map(|item| item.as_fn().cloned())
+        .collect();
 
     let impl_tok = ImplToken {
         attributes: attr,
+        unsafe_tok,
         generics,
+        negative_tok,
+        trait_ty,
         name,
-        // FIXME: make an abstraction for this kind of type
-        for_ty: None,
+        self_ty,
+        where_clause,
+        inn_attrs,
         raw_block: raw_impl_block,
+        associated_items,
         functions: funs,
     };
 
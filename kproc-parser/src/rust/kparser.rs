@@ -1,12 +1,14 @@
 use super::ast_nodes::{ImplToken, MethodDeclToken, TopLevelNode, TraitToken};
+use super::core::check_keywords;
+use super::kattr::prelude::*;
 use super::kenum::{self, EnumToken};
 use super::kfunc::parse_fn;
 use super::kimpl::parse_impl;
 use super::ktrait::parse_trait;
 use super::{ast_nodes::StructToken, kstruct::parse_struct};
 use crate::kparser::KParserError;
-use crate::proc_macro::TokenStream;
-use crate::{build_error, trace};
+use crate::proc_macro::{TokenStream, TokenTree};
+use crate::{build_error, parse_attributes, parse_visibility, trace};
 use crate::{
     kparser::{DummyTracer, KParserTracer},
     kproc_macros::KTokenStream,
@@ -26,6 +28,12 @@ pub struct RustParser<'tcx> {
     pub tracer: &'tcx dyn KParserTracer,
 }
 
+impl Default for RustParser<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<'tcx> RustParser<'tcx> {
     pub fn new() -> Self {
         RustParser {
@@ -37,38 +45,31 @@ impl<'tcx> RustParser<'tcx> {
         RustParser { tracer }
     }
 
+    /// parse the item by looking at its keyword, after the
+    /// attributes, the visibility and the qualifiers, so the
+    /// error is the one of the parser of that item.
     pub fn parse(&self, stream: &TokenStream) -> Result<TopLevelNode, KParserError> {
         let mut ast = KTokenStream::new(stream);
-        let first = ast.peek().clone();
-        if let Ok(tok) = parse_struct(&mut ast, self.tracer) {
-            return Ok(tok.into());
-        } else {
-            trace!(self.tracer, "error fom `parse_struct`");
-        }
-
-        let mut ast = KTokenStream::new(stream);
-        if let Ok(tok) = parse_impl(&mut ast, self.tracer) {
-            return Ok(tok.into());
-        } else {
-            trace!(self.tracer, "error fro `parse_impl`");
-        }
-
-        let mut ast = KTokenStream::new(stream);
-        if let Ok(tok) = parse_trait(&mut ast, self.tracer) {
-            return Ok(tok.into());
-        }
-
-        let mut ast = KTokenStream::new(stream);
-        if let Ok(tok) = kenum::parse(&mut ast, self.tracer) {
-            return Ok(tok.into());
-        }
-
-        let mut ast = KTokenStream::new(stream);
-        if let Ok(tok) = parse_fn(&mut ast, self.tracer) {
-            return Ok(tok.into());
+        let mut lookahead = ast.fork();
+        parse_attributes!(&mut lookahead, self.tracer)?;
+        parse_visibility!(&mut lookahead);
+        // skip the qualifiers like `unsafe` or `extern "C"`
+        while check_keywords(&lookahead, &["async", "const", "unsafe", "extern"], 0)
+            || matches!(lookahead.try_peek(), Ok(TokenTree::Literal(_)))
+        {
+            lookahead.next();
         }
-        let err = build_error!(first, "Token Stream sequence not known");
-        Err(err)
+        let keyword = lookahead.try_peek()?.clone();
+        trace!(self.tracer, "item keyword `{keyword}`");
+        let node = match keyword.to_string().as_str() {
+            "struct" => parse_struct(&mut ast, self.tracer)?.into(),
+            "impl" => parse_impl(&mut ast, self.tracer)?.into(),
+            "trait" => parse_trait(&mut ast, self.tracer)?.into(),
+            "enum" => kenum::parse(&mut ast, self.tracer)?.into(),
+            "fn" => parse_fn(&mut ast, self.tracer)?.into(),
+            _ => return Err(build_error!(keyword, "Token Stream sequence not known")),
+        };
+        Ok(node)
     }
 
     pub fn parse_struct(&self, stream: &TokenStream) -> StructToken {
@@ -0,0 +1,230 @@
+//! API to parse the rust patterns that are allowed
+//! inside the function parameters.
+use crate::kparser::{self, KParserError, KParserTracer};
+use crate::kproc_macros::{KTokenStream, MatchTok};
+use crate::proc_macro::{Delimiter, TokenTree};
+use crate::rust::ast_nodes::{FieldPattern, Pattern};
+use crate::rust::core::{check_and_parse_mut, check_path_separator};
+use crate::rust::keywords::{is_ident_tok, is_keyword_tok};
+use crate::{build_error, trace};
+
+/// parse a pattern without top alternatives, the pattern
+/// finish with the `:` of the type or with the `,` and
+/// both are not consumed.
+///
+/// PatternNoTopAlt :
+///    PatternWithoutRange
+///    | RangePattern
+pub fn parse_pattern(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<Pattern> {
+    let pattern = match stream.try_peek()?.clone() {
+        TokenTree::Group(group) => {
+            let mut inner_stream = stream.expect_group()?;
+            match group.delimiter() {
+                Delimiter::Parenthesis => {
+                    let (mut patterns, trailing_comma) =
+                        parse_pattern_list(&mut inner_stream, tracer)?;
+                    // a single pattern without the trailing comma is
+                    // only grouped, except for the rest pattern `(..)`.
+                    match patterns.as_slice() {
+                        [pattern] if !trailing_comma && !matches!(pattern, Pattern::Rest(_)) => {
+                            Pattern::Parenthesized(Box::new(patterns.remove(0)))
+                        }
+                        _ => Pattern::Tuple(patterns),
+                    }
+                }
+                Delimiter::Bracket => {
+                    Pattern::Slice(parse_pattern_list(&mut inner_stream, tracer)?.0)
+                }
+                _ => {
+                    return Err(build_error!(
+                        TokenTree::Group(group),
+                        "pattern not supported by the parser"
+                    ))
+                }
+            }
+        }
+        TokenTree::Punct(punct) if punct.as_char() == '&' => {
+            let ref_tok = stream.try_advance()?;
+            let mut_tok = check_and_parse_mut(stream);
+            let pattern = parse_pattern(stream, tracer)?;
+            Pattern::Reference {
+                ref_tok,
+                mut_tok,
+                pattern: Box::new(pattern),
+            }
+        }
+        TokenTree::Punct(punct) if punct.as_char() == '.' => {
+            let tok = stream.try_advance()?;
+            stream.expect_punct('.')?;
+            Pattern::Rest(tok)
+        }
+        ref tok @ TokenTree::Ident(_) if is_ident_tok(tok, "_") => {
+            Pattern::Wildcard(stream.try_advance()?)
+        }
+        ref tok @ TokenTree::Ident(_)
+            if is_keyword_tok(tok, "ref") || is_keyword_tok(tok, "mut") =>
+        {
+            parse_ident_pattern(stream, tracer)?.1
+        }
+        TokenTree::Ident(_) => {
+            let path = parse_path(stream)?;
+            let next = (!stream.is_end()).then(|| stream.peek().clone());
+            match next {
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                    let mut inner_stream = stream.expect_group()?;
+                    let (fields, rest) = parse_field_patterns(&mut inner_stream, tracer)?;
+                    Pattern::Struct { path, fields, rest }
+                }
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                    let mut inner_stream = stream.expect_group()?;
+                    let (patterns, _) = parse_pattern_list(&mut inner_stream, tracer)?;
+                    Pattern::TupleStruct { path, patterns }
+                }
+                _ if path.len() == 1 => Pattern::Ident {
+                    ref_tok: None,
+                    mut_tok: None,
+                    ident: path[0].clone(),
+                    subpattern: check_and_parse_subpattern(stream, tracer)?,
+                },
+                _ => {
+                    return Err(build_error!(
+                        path[0].clone(),
+                        "path pattern not supported by the parser"
+                    ))
+                }
+            }
+        }
+        tok => {
+            return Err(build_error!(
+                tok.clone(),
+                "pattern `{tok}` not supported by the parser"
+            ))
+        }
+    };
+    trace!(tracer, "pattern found `{pattern}`");
+    Ok(pattern)
+}
+
+/// parse the identifier pattern and return also the identifier.
+///
+/// IdentifierPattern :
+///    ref? mut? IDENTIFIER (@ PatternNoTopAlt ) ?
+fn parse_ident_pattern(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<(TokenTree, Pattern)> {
+    let ref_tok = stream.next_if(|tok| is_keyword_tok(tok, "ref"));
+    let mut_tok = check_and_parse_mut(stream);
+    let ident = stream.expect_ident()?;
+    let pattern = Pattern::Ident {
+        ref_tok,
+        mut_tok,
+        ident: ident.clone(),
+        subpattern: check_and_parse_subpattern(stream, tracer)?,
+    };
+    Ok((ident, pattern))
+}
+
+/// parse the pattern bound to an identifier with `@`, like
+/// `rest @ ..`, if it is present.
+fn check_and_parse_subpattern(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<Option<Box<Pattern>>> {
+    if stream.next_if(|tok| tok.match_tok("@")).is_none() {
+        return Ok(None);
+    }
+    Ok(Some(Box::new(parse_pattern(stream, tracer)?)))
+}
+
+/// parse the path of a struct pattern like `a::Point`
+fn parse_path(stream: &mut KTokenStream) -> kparser::Result<Vec<TokenTree>> {
+    let mut path = vec![stream.expect_ident()?];
+    while check_path_separator(stream, 0) {
+        path.push(stream.try_advance()?);
+        path.push(stream.try_advance()?);
+        path.push(stream.expect_ident()?);
+    }
+    Ok(path)
+}
+
+/// parse a sequence of pattern separated by `,` until the
+/// end of the stream, and return if there is a trailing comma.
+fn parse_pattern_list(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<(Vec<Pattern>, bool)> {
+    let mut patterns = vec![];
+    let mut trailing_comma = false;
+    while !stream.is_end() {
+        patterns.push(parse_pattern(stream, tracer)?);
+        trailing_comma = false;
+        if !stream.is_end() {
+            stream.expect_punct(',')?;
+            trailing_comma = true;
+        }
+    }
+    Ok((patterns, trailing_comma))
+}
+
+/// StructPatternElements :
+///    StructPatternFields (, | , StructPatternEtCetera)?
+///    | StructPatternEtCetera
+fn parse_field_patterns(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<(Vec<FieldPattern>, Option<TokenTree>)> {
+    let mut fields = vec![];
+    let mut rest = None;
+    while !stream.is_end() {
+        if stream.match_tok(".") {
+            rest = Some(stream.try_advance()?);
+            stream.expect_punct('.')?;
+        } else if stream.match_tok("ref") || stream.match_tok("mut") {
+            let (ident, pattern) = parse_ident_pattern(stream, tracer)?;
+            fields.push(FieldPattern {
+                ident,
+                pattern,
+                shorthand: true,
+            });
+        } else if matches!(stream.try_peek()?, TokenTree::Literal(_)) {
+            // the tuple index like `0: value` has always a pattern
+            let ident = stream.try_advance()?;
+            stream.expect_punct(':')?;
+            fields.push(FieldPattern {
+                ident,
+                pattern: parse_pattern(stream, tracer)?,
+                shorthand: false,
+            });
+        } else {
+            let ident = stream.expect_ident()?;
+            let field = if !stream.is_end() && stream.match_tok(":") {
+                stream.next(); // consume `:`
+                FieldPattern {
+                    ident,
+                    pattern: parse_pattern(stream, tracer)?,
+                    shorthand: false,
+                }
+            } else {
+                FieldPattern {
+                    ident: ident.clone(),
+                    pattern: Pattern::Ident {
+                        ref_tok: None,
+                        mut_tok: None,
+                        ident,
+                        subpattern: None,
+                    },
+                    shorthand: true,
+                }
+            };
+            fields.push(field);
+        }
+        if !stream.is_end() {
+            stream.expect_punct(',')?;
+        }
+    }
+    Ok((fields, rest))
+}
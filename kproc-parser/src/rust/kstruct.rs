@@ -1,15 +1,14 @@
 //! API to parse the rust struct provided as
 //! TokenStream.
-use std::collections::HashMap;
-
 use crate::kparser::{KParserError, KParserTracer};
 use crate::kproc_macros::KTokenStream;
-use crate::rust::ast_nodes::{FieldToken, StructToken};
+use crate::proc_macro::{Delimiter, Literal, TokenTree};
+use crate::rust::ast_nodes::{Attributes, FieldToken, StructKind, StructToken};
 use crate::rust::ty::parse_ty;
-use crate::{build_error, check, parse_visibility, trace};
+use crate::{build_error, parse_attributes, parse_visibility, trace};
 
 use super::core::*;
-use super::kattr::check_and_parse_cond_attribute;
+use super::kattr::prelude::*;
 
 /// parsing a rust data structure inside a AST that will be easy to
 /// manipulate and use by a compiler
@@ -17,23 +16,42 @@ pub fn parse_struct(
     stream: &mut KTokenStream,
     tracer: &dyn KParserTracer,
 ) -> Result<StructToken, KParserError> {
-    let attrs = check_and_parse_cond_attribute(stream, tracer);
+    let attrs = parse_attributes!(stream, tracer)?;
     let visibility = parse_visibility!(stream);
-    let tok = stream.advance();
-    check!("struct", tok)?;
+    stream.expect_tok("struct")?;
 
-    let name = stream.advance();
+    let name = stream.expect_ident()?;
     let generics = check_and_parse_generics_params(stream, tracer)?;
 
-    let mut group = stream.to_ktoken_stream();
-    let fields = parse_struct_fields(&mut group, tracer)?;
+    let mut where_clause = check_and_parse_where_clause(stream, tracer)?;
+    let next = (!stream.is_end()).then(|| stream.peek().clone());
+    let (kind, fields) = match next {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+            let mut group = stream.expect_group()?;
+            (StructKind::Named, parse_struct_fields(&mut group, tracer)?)
+        }
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            let mut group = stream.expect_group()?;
+            let fields = parse_tuple_fields(&mut group, tracer)?;
+            // the where clause of a tuple struct is after the fields
+            where_clause = check_and_parse_where_clause(stream, tracer)?;
+            stream.expect_punct(';')?;
+            (StructKind::Tuple, fields)
+        }
+        _ => {
+            stream.expect_punct(';')?;
+            (StructKind::Unit, vec![])
+        }
+    };
 
     let struct_tok = StructToken {
         attrs,
         visibility,
         name,
+        kind,
         fields,
         generics,
+        where_clause,
     };
     trace!(tracer, "`parse_struct` result {:#?}", struct_tok);
     Ok(struct_tok)
@@ -45,10 +63,10 @@ pub fn parse_struct_fields(
 ) -> Result<Vec<FieldToken>, KParserError> {
     let mut fields = vec![];
     while !ast.is_end() {
-        let attr = check_and_parse_cond_attribute(ast, tracer);
-        trace!(tracer, "after token {:?}", ast.peek());
+        let attrs = parse_attributes!(ast, tracer)?;
+        trace!(tracer, "after token {:?}", ast.try_peek()?);
         let mut field = parse_struct_ty(ast, tracer)?;
-        field.attrs.extend(attr);
+        field.attrs.extend(attrs);
         fields.push(field);
     }
     Ok(fields)
@@ -60,9 +78,8 @@ pub fn parse_struct_ty(
 ) -> Result<FieldToken, KParserError> {
     // name filed
     let visibility = parse_visibility!(ast);
-    let field_name = ast.advance();
-    let separator = ast.advance();
-    check!(":", separator)?;
+    let field_name = ast.expect_ident()?;
+    let separator = ast.expect_punct(':')?;
 
     let ty = parse_ty(ast, tracer)?.ok_or(build_error!(
         separator,
@@ -74,7 +91,43 @@ pub fn parse_struct_ty(
         visibility,
         identifier: field_name,
         ty,
-        attrs: HashMap::new(),
+        attrs: Attributes::new(),
     };
     Ok(field)
 }
+
+/// parse the fields of a tuple struct, that are
+/// positional so the identifier of each field is the
+/// index literal of the field.
+///
+/// TupleFields :
+///    TupleField (, TupleField)* ,?
+///
+/// TupleField :
+///    OuterAttribute* Visibility? Type
+pub fn parse_tuple_fields(
+    ast: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> Result<Vec<FieldToken>, KParserError> {
+    let mut fields = vec![];
+    while !ast.is_end() {
+        let attrs = parse_attributes!(ast, tracer)?;
+        let visibility = parse_visibility!(ast);
+        let start = ast.try_peek()?.clone();
+        let ty = parse_ty(ast, tracer)?.ok_or(build_error!(
+            start.clone(),
+            "failing to parse a type, it may be a Trait Bound so please report a bug"
+        ))?;
+        trace!(tracer, "tuple field {}: {ty}", fields.len());
+
+        let mut index = Literal::usize_unsuffixed(fields.len());
+        index.set_span(start.span());
+        fields.push(FieldToken {
+            attrs,
+            visibility,
+            identifier: TokenTree::Literal(index),
+            ty,
+        });
+    }
+    Ok(fields)
+}
@@ -1,9 +1,10 @@
 use crate::kparser::{KParserError, KParserTracer};
-use crate::kproc_macros::KTokenStream;
+use crate::kproc_macros::{KTokenStream, MatchTok};
 use crate::rust::core::*;
-use crate::rust::kattr::check_and_parse_cond_attribute;
-use crate::rust::kfunc::parse_fn;
-use crate::{check, parse_visibility, trace};
+use crate::rust::kassoc::parse_associated_items;
+use crate::rust::kattr::prelude::*;
+use crate::rust::keywords::is_keyword_tok;
+use crate::{parse_attributes, parse_visibility, trace};
 
 use super::ast_nodes::TraitToken;
 
@@ -13,35 +14,69 @@ pub fn parse_trait(
     tracer: &dyn KParserTracer,
 ) -> Result<TraitToken, KParserError> {
     trace!(tracer, "start parning the trait");
-    let attrs = check_and_parse_cond_attribute(ast, tracer);
+    let attrs = parse_attributes!(ast, tracer)?;
 
     let vist = parse_visibility!(ast);
-    let trait_tok = ast.advance();
-    check!("trait", trait_tok)?;
-    let name = ast.advance();
-    trace!(tracer, "`{name}` checking bounds on `{:?}`", ast.peek());
-    let generics = check_and_parse_bounds(ast, tracer)?;
+    let unsafe_tok = ast.next_if(|tok| is_keyword_tok(tok, "unsafe"));
+    ast.expect_tok("trait")?;
+    let name = ast.expect_ident()?;
+    trace!(
+        tracer,
+        "`{name}` checking bounds on `{:?}`",
+        ast.try_peek()?
+    );
+    let generics = check_and_parse_generics_params(ast, tracer)?;
+    let bounds = if ast.next_if(|tok| tok.match_tok(":")).is_some() {
+        parse_type_param_bounds(ast, tracer)?
+    } else {
+        vec![]
+    };
+    let where_clause = check_and_parse_where_clause(ast, tracer)?;
     trace!(tracer, "checking the trait block");
-    let raw_block = ast.unwrap_group_as_stream();
-    let mut block = ast.to_ktoken_stream();
+    let mut block = ast.expect_group()?;
+    let raw_block = block.to_stream();
+    let inn_attrs = check_and_parse_inner_attributes(&mut block, tracer)?;
 
-    let mut funs = Vec::new();
-    while !block.is_end() {
-        trace!(tracer, "checking body");
-        let fn_tok = parse_fn(&mut block, tracer)?;
-        funs.push(fn_tok);
-    }
+    trace!(tracer, "checking body");
+    let associated_items = parse_associated_items(&mut block, tracer)?;
+    let funs = associated_items
+        .iter()
+        .filter_map(|item| item.as_fn().cloned())
+        .collect();
 
     let trait_tok = TraitToken {
         attrs,
         visibility: vist,
+        unsafe_tok,
         ident: name,
         generics,
-        inn_attrs: None, // FIXME: parse this
-        associated_items: vec![],
+        bounds,
+        where_clause,
+        inn_attrs,
+        associated_items,
         raw_block,
         functions: funs,
     };
     trace!(tracer, "trait token result: {:#?}", trait_tok);
     Ok(trait_tok)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kparser::DummyTracer;
+    use crate::proc_macro::TokenStream;
+
+    #[test]
+    fn supertraits_are_parsed() {
+        let stream: TokenStream = "pub unsafe trait T<A>: Clone + Send where A: Debug { }"
+            .parse()
+            .unwrap();
+        let ast = parse_trait(&mut KTokenStream::new(&stream), &DummyTracer {}).unwrap();
+        assert_eq!(ast.bounds.len(), 2);
+        assert_eq!(
+            ast.to_string(),
+            "pub unsafe trait T<A>: Clone + Send where A: Debug {  }"
+        );
+    }
+}
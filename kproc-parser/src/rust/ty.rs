@@ -1,100 +1,627 @@
 //! Type parser to allow a more flexible
 //! parser.
-use super::ast_nodes::TyToken;
+use super::ast_nodes::{
+    BareFnTy, Bound, GenericArg, ParenthesizedArgs, PathSegment, TraitBound, TyToken,
+};
 use crate::build_error;
 use crate::kparser;
 use crate::kparser::{KParserError, KParserTracer};
-use crate::kproc_macros::KTokenStream;
+use crate::kproc_macros::{KTokenStream, MatchTok};
+use crate::proc_macro::{Delimiter, TokenStream, TokenTree};
 use crate::rust::ast_nodes::LifetimeParam;
 use crate::rust::ast_nodes::TyKind;
 use crate::rust::core::check_and_parse_dyn;
+use crate::rust::core::check_and_parse_for_lifetimes;
 use crate::rust::core::check_and_parse_lifetime;
 use crate::rust::core::check_and_parse_mut;
 use crate::rust::core::check_and_parse_ref;
-use crate::trace;
+use crate::rust::core::check_keyword;
+use crate::rust::core::check_keywords;
+use crate::rust::core::check_path_separator;
+use crate::rust::core::check_tok;
+use crate::rust::core::parse_type_param_bounds;
+use crate::rust::keywords::{is_ident_tok, is_keyword_tok};
+use crate::{check, trace};
 
 /// parse the field type as an AST element, and return the type field,
 /// if found, otherwise if the type is a Trait bound return None.
+///
+/// The `,` after the type is consumed, and all the other stop
+/// tokens are left inside the stream.
 pub fn parse_ty(
     stream: &mut KTokenStream,
     tracer: &dyn KParserTracer,
 ) -> kparser::Result<Option<TyToken>> {
-    let ref_tok = check_and_parse_ref(stream);
-    let lifetime = check_and_parse_lifetime(stream).map(|lifetime| LifetimeParam {
-        lifetime_or_label: lifetime,
-        bounds: Vec::new(),
-    });
-    let dyn_tok = check_and_parse_dyn(stream);
-    let mut_tok = check_and_parse_mut(stream);
-    let identifier = stream.advance();
-    trace!(tracer, "type `{identifier}`");
+    let ty = parse_ty_kind(stream, tracer)?;
     // in the case of the function parameters here  we ca be
     // at the end of the stream
-    //
-    // In addition the basics types do not need
-    // the generics check, and in the case of EOF
-    // checking the generic will panic the parser.
-    let mut generics: Option<Vec<TyToken>> = None;
     if !stream.is_end() {
-        trace!(
-            tracer,
-            "parsing generics `{identifier}<...>`, next tok {}",
-            stream.peek()
-        );
-        let subtypes = parse_recursive_ty(stream, tracer)?;
-        trace!(tracer, "finish parsing recursive ty: {:?}", subtypes);
-        if !subtypes.is_empty() {
-            generics = Some(subtypes);
-        }
+        let sep = stream.peek().to_owned();
 
-        if !stream.is_end() {
-            let sep = stream.peek().to_owned();
+        // token allowed as stop words for the type parser
+        if ![",", ">", ";", ":", "+", "=", "where", "for", "as"].contains(&sep.to_string().as_str())
+            && !stream.is_group()
+        {
+            return Err(build_error!(sep, "unexpected separator `{sep}`"));
+        }
+        // token to consume, but in this case
+        // we do not consume the `>`
+        // because we are in a recursive call,
+        // and the token is a stop word for the
+        // root recursive call.
+        if [","].contains(&sep.to_string().as_str()) {
+            stream.next();
+        }
+    }
+    trace!(tracer, "type found `{ty}`");
+    Ok(Some(ty))
+}
 
-            // token allowed as stop words for the type parser
-            if ![",", ">", ";"].contains(&sep.to_string().as_str()) && !stream.is_group() {
-                assert!(false, "unexpected separator `{:?}`", sep);
+/// parse the type by looking at the first token to
+/// understand the kind of the type.
+///
+/// Type :
+///    TypeNoBounds
+///    | ImplTraitType
+///    | TraitObjectType
+fn parse_ty_kind(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<TyToken> {
+    let first = stream.try_peek()?.clone();
+    let ty = match &first {
+        TokenTree::Punct(punct) if punct.as_char() == '&' => parse_reference_ty(stream, tracer)?,
+        TokenTree::Punct(punct) if punct.as_char() == '*' => {
+            let star = stream.try_advance()?;
+            let qualifier = stream.try_advance()?;
+            if !check_identifiers_tok(&qualifier, &["const", "mut"]) {
+                return Err(build_error!(
+                    qualifier,
+                    "expected `const` or `mut` after the `*` of a raw pointer"
+                ));
             }
-            // token to consume, but in this case
-            // we do not consume the `>`
-            // because we are in a recursive call,
-            // and the token is a stop word for the
-            // root recursive call.
-            if [","].contains(&sep.to_string().as_str()) {
-                stream.next();
+            let ty = parse_ty_kind(stream, tracer)?;
+            TyToken::new(
+                TyKind::RawPointerType {
+                    mutable: qualifier.to_string() == "mut",
+                    ty: Box::new(ty),
+                },
+                star,
+            )
+        }
+        TokenTree::Punct(punct) if punct.as_char() == '!' => {
+            TyToken::new(TyKind::NeverType, stream.try_advance()?)
+        }
+        TokenTree::Punct(punct) if punct.as_char() == '<' => {
+            parse_qualified_path_ty(stream, tracer)?
+        }
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis => {
+            let group_tok = stream.peek().clone();
+            let mut inner = stream.expect_group()?;
+            let (mut types, trailing_comma) = parse_ty_list(&mut inner, tracer)?;
+            if types.len() == 1 && !trailing_comma {
+                TyToken::new(TyKind::Parenthesized(Box::new(types.remove(0))), group_tok)
+            } else {
+                TyToken::new(TyKind::TupleType(types), group_tok)
+            }
+        }
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Bracket => {
+            let group_tok = stream.peek().clone();
+            let mut inner = stream.expect_group()?;
+            let ty = Box::new(parse_ty_kind(&mut inner, tracer)?);
+            if inner.is_end() {
+                TyToken::new(TyKind::SliceType(ty), group_tok)
+            } else {
+                inner.expect_punct(';')?;
+                // the length expression can not be empty
+                let mut len = vec![inner.try_advance()?];
+                while !inner.is_end() {
+                    len.push(inner.try_advance()?);
+                }
+                let len = TokenStream::from_iter(len);
+                TyToken::new(TyKind::ArrayType { ty, len }, group_tok)
             }
         }
+        tok @ TokenTree::Ident(_) if is_ident_tok(tok, "_") => {
+            TyToken::new(TyKind::InferredType, stream.try_advance()?)
+        }
+        tok @ TokenTree::Ident(_) if is_keyword_tok(tok, "impl") => {
+            let impl_tok = stream.try_advance()?;
+            let mut ty = parse_trait_path(stream, tracer, impl_tok)?;
+            ty.kind = TyKind::ImplTrait;
+            ty
+        }
+        tok @ TokenTree::Ident(_) if is_keyword_tok(tok, "dyn") => {
+            let dyn_tok = check_and_parse_dyn(stream);
+            let mut ty = parse_trait_path(stream, tracer, first.clone())?;
+            ty.kind = TyKind::TraitObject;
+            ty.dyn_tok = dyn_tok;
+            ty
+        }
+        TokenTree::Ident(_) if is_bare_fn(stream) => parse_bare_fn_ty(stream, tracer)?,
+        _ if is_macro_invocation(stream) => parse_macro_invocation_ty(stream)?,
+        _ => parse_type_path(stream, tracer)?,
+    };
+    Ok(ty)
+}
+
+/// look ahead the stream to check if the type is a macro
+/// invocation like `a::b!(c)`, without consuming it.
+fn is_macro_invocation(stream: &KTokenStream) -> bool {
+    let mut pos = 0;
+    if check_path_separator(stream, pos) {
+        pos += 2;
+    }
+    while stream.has(pos) && matches!(stream.lookup(pos), TokenTree::Ident(_)) {
+        pos += 1;
+        if !check_path_separator(stream, pos) {
+            return stream.has(pos) && stream.lookup(pos).match_tok("!");
+        }
+        pos += 2;
     }
+    false
+}
 
-    trace!(tracer, "Type `{identifier} with generic `{:?}`", generics);
-    Ok(Some(TyToken {
+/// MacroInvocation :
+///    SimplePath ! DelimTokenTree
+fn parse_macro_invocation_ty(stream: &mut KTokenStream) -> kparser::Result<TyToken> {
+    let mut path = Vec::new();
+    if check_path_separator(stream, 0) {
+        path.push(stream.try_advance()?);
+        path.push(stream.try_advance()?);
+    }
+    path.push(stream.expect_ident()?);
+    while check_path_separator(stream, 0) {
+        path.push(stream.try_advance()?);
+        path.push(stream.try_advance()?);
+        path.push(stream.expect_ident()?);
+    }
+    stream.expect_punct('!')?;
+    if !stream.is_group() {
+        return Err(build_error!(
+            stream.try_peek()?.clone(),
+            "expected a delimited token tree after the macro name"
+        ));
+    }
+    let tokens = stream.try_advance()?;
+    let identifier = path[0].clone();
+    Ok(TyToken::new(
+        TyKind::MacroInvocation { path, tokens },
         identifier,
-        dyn_tok,
+    ))
+}
+
+/// ReferenceType :
+///    & Lifetime? mut? TypeNoBounds
+fn parse_reference_ty(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<TyToken> {
+    let ref_tok = check_and_parse_ref(stream);
+    let lifetime = check_and_parse_lifetime(stream).map(|lifetime| LifetimeParam {
+        lifetime_or_label: lifetime,
+        bounds: Vec::new(),
+    });
+    let mut_tok = check_and_parse_mut(stream);
+    let inner = parse_ty_kind(stream, tracer)?;
+    Ok(TyToken {
         ref_tok,
         mut_tok,
         lifetime,
-        generics,
+        identifier: inner.identifier.clone(),
+        dyn_tok: inner.dyn_tok.clone(),
+        generics: inner.generics.clone(),
+        leading_colon: inner.leading_colon.clone(),
+        segments: inner.segments.clone(),
         bounds: vec![],
-        // FIXME: try to understnad how to parse the `TyKind` or if we
-        // really need it.
-        kind: TyKind::NeverType,
-    }))
+        kind: TyKind::ReferenceType(Box::new(inner)),
+    })
+}
+
+/// parse a type path like `std::vec::Vec<T>` and return the type.
+///
+/// TypePath :
+///    ::? TypePathSegment (:: TypePathSegment)*
+fn parse_type_path(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<TyToken> {
+    let leading_colon = if check_path_separator(stream, 0) {
+        let colon = stream.try_advance()?;
+        stream.next(); // consume the second `:`
+        Some(colon)
+    } else {
+        None
+    };
+    let mut segments = vec![parse_path_segment(stream, tracer)?];
+    while check_path_separator(stream, 0) {
+        stream.next();
+        stream.next();
+        segments.push(parse_path_segment(stream, tracer)?);
+    }
+    // the identifier and the generics of the type are
+    // the one of the last segment of the path.
+    let last = segments.last().cloned().unwrap();
+    trace!(tracer, "type `{}`", last.ident);
+    let mut ty = TyToken::new(TyKind::TypePath, last.ident);
+    ty.generics = last.generics;
+    ty.leading_colon = leading_colon;
+    ty.segments = segments;
+    Ok(ty)
+}
+
+/// parse a segment of the path with the generics arguments,
+/// also when the generics are declared with the turbofish
+/// syntax `::<T>`.
+///
+/// TypePathSegment :
+///    PathIdentSegment (::? GenericArgs)?
+fn parse_path_segment(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<PathSegment> {
+    let ident = stream.try_advance()?;
+    if !matches!(ident, TokenTree::Ident(_)) {
+        return Err(build_error!(
+            ident,
+            "expected an identifier inside the type path"
+        ));
+    }
+    if check_path_separator(stream, 0) && stream.has(2) && stream.lookup(2).match_tok("<") {
+        stream.next();
+        stream.next();
+    }
+    // In addition the basics types do not need
+    // the generics check, and in the case of EOF
+    // checking the generic will panic the parser.
+    let generics = if stream.is_end() {
+        vec![]
+    } else {
+        parse_recursive_ty(stream, tracer)?
+    };
+    trace!(tracer, "finish parsing recursive ty: {:?}", generics);
+    Ok(PathSegment {
+        ident,
+        generics: (!generics.is_empty()).then_some(generics),
+    })
+}
+
+/// parse the bounds of a trait object or an impl trait type,
+/// like `Trait<T> + Send`, where the path of the first trait
+/// is copied inside the type.
+///
+/// ImplTraitType : impl TypeParamBounds
+///
+/// TraitObjectType : dyn? TypeParamBounds
+fn parse_trait_path(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+    start: TokenTree,
+) -> kparser::Result<TyToken> {
+    let bounds = parse_type_param_bounds(stream, tracer)?;
+    let first_trait = bounds.iter().find_map(|bound| match bound {
+        Bound::Trait(bound) => Some(bound),
+        Bound::Lifetime(_) => None,
+    });
+    let mut ty = match first_trait {
+        Some(bound) => bound.path.as_ref().clone(),
+        None => TyToken::new(TyKind::TypePath, start.clone()),
+    };
+    if bounds.is_empty() {
+        return Err(build_error!(start, "expected at least one bound"));
+    }
+    ty.bounds = bounds;
+    Ok(ty)
+}
+
+/// parse a trait bound like `?Sized`, `Into<String>`
+/// or `for<'a> Fn(&'a str) -> bool`.
+///
+/// TraitBound :
+///    ?? ForLifetimes? TypePath
+pub fn parse_trait_bound(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<TraitBound> {
+    let mut for_lifetimes = check_and_parse_for_lifetimes(stream)?.unwrap_or_default();
+    let maybe_tok = stream.next_if(|tok| tok.match_tok("?"));
+    let const_tok = if stream.match_tok("~") {
+        stream.next(); // consume `~`
+        Some(stream.expect_tok("const")?)
+    } else {
+        None
+    };
+    if for_lifetimes.is_empty() {
+        for_lifetimes = check_and_parse_for_lifetimes(stream)?.unwrap_or_default();
+    }
+    let path = parse_type_path(stream, tracer)?;
+    // the parenthesized arguments of the `Fn` traits
+    let fn_args = match (!stream.is_end()).then(|| stream.peek()) {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            let mut inner = stream.expect_group()?;
+            let (inputs, _) = parse_ty_list(&mut inner, tracer)?;
+            let output =
+                if stream.has(1) && stream.match_tok("-") && stream.lookup(1).match_tok(">") {
+                    stream.next(); // consume `-`
+                    stream.next(); // consume `>`
+                    Some(Box::new(parse_ty_kind(stream, tracer)?))
+                } else {
+                    None
+                };
+            Some(ParenthesizedArgs { inputs, output })
+        }
+        _ => None,
+    };
+    Ok(TraitBound {
+        for_lifetimes,
+        maybe_tok,
+        const_tok,
+        path: Box::new(path),
+        fn_args,
+    })
+}
+
+/// QualifiedPathInType :
+///    QualifiedPathType (:: TypePathSegment)+
+///
+/// QualifiedPathType :
+///    < Type (as TypePath)? >
+fn parse_qualified_path_ty(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<TyToken> {
+    let start = stream.expect_punct('<')?;
+    let self_ty = parse_ty_kind(stream, tracer)?;
+    let trait_ty = if check_keyword(stream, "as", 0) {
+        stream.next(); // consume `as`
+        Some(Box::new(parse_ty_kind(stream, tracer)?))
+    } else {
+        None
+    };
+    stream.expect_punct('>')?;
+    let mut segments = Vec::new();
+    while check_path_separator(stream, 0) {
+        stream.next();
+        stream.next();
+        segments.push(parse_path_segment(stream, tracer)?);
+    }
+    if segments.is_empty() {
+        return Err(build_error!(
+            start,
+            "expected a path segment after the qualified path"
+        ));
+    }
+    Ok(TyToken::new(
+        TyKind::QualifiedPathInType {
+            self_ty: Box::new(self_ty),
+            trait_ty,
+            segments,
+        },
+        start,
+    ))
+}
+
+fn is_bare_fn(stream: &KTokenStream) -> bool {
+    check_keywords(stream, &["fn", "unsafe", "extern"], 0)
+        || (check_keyword(stream, "for", 0) && check_tok(stream, "<", 1))
+}
+
+/// BareFunctionType :
+///    ForLifetimes? FunctionTypeQualifiers fn
+///        ( FunctionParametersMaybeNamedVariadic? ) BareFunctionReturnType?
+fn parse_bare_fn_ty(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<TyToken> {
+    let for_lifetimes = check_and_parse_for_lifetimes(stream)?.unwrap_or_default();
+    let mut qualifiers = Vec::new();
+    while check_keywords(stream, &["unsafe", "extern"], 0) {
+        let qualifier = stream.try_advance()?;
+        let is_extern = qualifier.to_string() == "extern";
+        qualifiers.push(qualifier);
+        // the ABI of the extern like `extern "C"`
+        if is_extern && matches!(stream.try_peek()?, TokenTree::Literal(_)) {
+            qualifiers.push(stream.try_advance()?);
+        }
+    }
+    let fn_tok = stream.expect_tok("fn")?;
+    let mut raw_params = stream.expect_group()?;
+    let mut inputs = Vec::new();
+    while !raw_params.is_end() {
+        // the name of the parameter is optional
+        let name = if raw_params.has(1)
+            && raw_params.lookup(1).match_tok(":")
+            && !check_path_separator(&raw_params, 1)
+        {
+            let name = raw_params.try_advance()?;
+            raw_params.next(); // consume `:`
+            Some(name)
+        } else {
+            None
+        };
+        let ty = parse_ty(&mut raw_params, tracer)?.ok_or(build_error!(
+            fn_tok.clone(),
+            "failing to parse the parameter type, this is a bug, please report it"
+        ))?;
+        inputs.push((name, ty));
+    }
+    // the return type is parsed without consuming the `,` that follow,
+    // because it belongs to the list where the fn type is declared.
+    let output = if stream.has(1) && stream.match_tok("-") && stream.lookup(1).match_tok(">") {
+        stream.next(); // consume `-`
+        stream.next(); // consume `>`
+        Some(Box::new(parse_ty_kind(stream, tracer)?))
+    } else {
+        None
+    };
+    Ok(TyToken::new(
+        TyKind::BareFunctionType(Box::new(BareFnTy {
+            for_lifetimes,
+            qualifiers,
+            inputs,
+            output,
+        })),
+        fn_tok,
+    ))
+}
+
+/// parse a sequence of types separated by `,` until the end
+/// of the stream, and return if there is a trailing comma.
+fn parse_ty_list(
+    stream: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<(Vec<TyToken>, bool)> {
+    let mut types = Vec::new();
+    let mut trailing_comma = false;
+    while !stream.is_end() {
+        types.push(parse_ty_kind(stream, tracer)?);
+        trailing_comma = false;
+        if !stream.is_end() {
+            check!(",", stream.try_advance()?)?;
+            trailing_comma = true;
+        }
+    }
+    Ok((types, trailing_comma))
 }
 
+fn check_identifiers_tok(tok: &TokenTree, idents: &[&str]) -> bool {
+    matches!(tok, TokenTree::Ident(ident) if idents.contains(&ident.to_string().as_str()))
+}
+
+/// parse the generic arguments of a path segment like
+/// `<'a, T, 3, Item = u8>`, if the stream do not start
+/// with `<` an empty vector is returned.
+///
+/// GenericArgs :
+///    < >
+///    | < ( GenericArg , )* GenericArg ,? >
 pub fn parse_recursive_ty(
     ast: &mut KTokenStream,
     tracer: &dyn KParserTracer,
-) -> kparser::Result<Vec<TyToken>> {
-    let mut types: Vec<TyToken> = vec![];
+) -> kparser::Result<Vec<GenericArg>> {
+    let mut args: Vec<GenericArg> = vec![];
     if ast.match_tok("<") {
         ast.next(); // consume `<``
         while !ast.match_tok(">") {
-            let ty = parse_ty(ast, tracer)?.ok_or(build_error!(
-                ast.peek().clone(),
-                "failing to parse the type, this is a bug, please report it"
-            ))?;
-            types.push(ty);
+            let arg = parse_generic_arg(ast, tracer)?;
+            trace!(tracer, "generic argument found `{arg}`");
+            args.push(arg);
+            if ast.match_tok(",") {
+                ast.next();
+            } else if !ast.match_tok(">") {
+                let tok = ast.try_peek()?.clone();
+                return Err(build_error!(
+                    tok,
+                    "expected `,` or `>` after the generic argument, but found `{tok}`"
+                ));
+            }
         }
         ast.next(); // consume the `>` toks
     }
-    Ok(types)
+    Ok(args)
+}
+
+/// GenericArg :
+///    Lifetime | Type | GenericArgsConst | GenericArgsBinding
+fn parse_generic_arg(
+    ast: &mut KTokenStream,
+    tracer: &dyn KParserTracer,
+) -> kparser::Result<GenericArg> {
+    if let Some(lifetime) = check_and_parse_lifetime(ast) {
+        return Ok(GenericArg::Lifetime(LifetimeParam {
+            lifetime_or_label: lifetime,
+            bounds: vec![],
+        }));
+    }
+    match ast.try_peek()? {
+        TokenTree::Literal(_) => return Ok(GenericArg::Const(ast.try_advance()?.into())),
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+            return Ok(GenericArg::Const(ast.try_advance()?.into()))
+        }
+        TokenTree::Punct(punct) if punct.as_char() == '-' => {
+            let minus = ast.try_advance()?;
+            let literal = ast.try_advance()?;
+            return Ok(GenericArg::Const(TokenStream::from_iter([minus, literal])));
+        }
+        _ => {}
+    }
+    let ty = parse_ty_kind(ast, tracer)?;
+    // the binding of an associated type like `Item = u8`
+    // is parsed as a type path until the `=` is found.
+    if ast.match_tok("=") && ty.leading_colon.is_none() && ty.segments.len() == 1 {
+        ast.next(); // consume `=`
+        let segment = ty.segments[0].clone();
+        return Ok(GenericArg::Binding {
+            ident: segment.ident,
+            generics: segment.generics,
+            ty: parse_ty_kind(ast, tracer)?,
+        });
+    }
+    Ok(GenericArg::Type(ty))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kparser::DummyTracer;
+    use crate::rust::kpattern::parse_pattern;
+
+    #[test]
+    fn rewind_after_failed_alternative() {
+        let tracer = DummyTracer {};
+        let source: TokenStream = "&'a mut Vec<u8>, u32".parse().unwrap();
+        let mut expected_toks = KTokenStream::new(&source);
+        let expected = parse_ty(&mut expected_toks, &tracer).unwrap().unwrap();
+
+        let mut toks = KTokenStream::new(&source);
+        let checkpoint = toks.checkpoint();
+        // a reference pattern does not accept a lifetime, so the
+        // alternative fails after consuming the `&`.
+        assert!(parse_pattern(&mut toks, &tracer).is_err());
+        assert_ne!(toks.checkpoint(), checkpoint);
+
+        toks.rewind(checkpoint);
+        assert_eq!(toks.checkpoint(), checkpoint);
+        let ty = parse_ty(&mut toks, &tracer).unwrap().unwrap();
+        assert_eq!(format!("{ty:?}"), format!("{expected:?}"));
+        assert_eq!(ty.to_string(), expected.to_string());
+        // the `,` after the type is consumed by `parse_ty`
+        assert_eq!(toks.checkpoint(), expected_toks.checkpoint());
+        assert_eq!(toks.try_advance().unwrap().to_string(), "u32");
+    }
+
+    #[test]
+    fn raw_identifiers_are_type_paths() {
+        let tracer = DummyTracer {};
+        for source in ["r#dyn", "r#impl"] {
+            let stream: TokenStream = source.parse().unwrap();
+            let ty = parse_ty(&mut KTokenStream::new(&stream), &tracer)
+                .unwrap()
+                .unwrap();
+            assert!(
+                matches!(ty.kind, TyKind::TypePath),
+                "`{source}` is not a path"
+            );
+        }
+    }
+
+    #[test]
+    fn macro_invocation_with_path() {
+        let tracer = DummyTracer {};
+        for (source, expected) in [
+            ("a::b!(c)", "a::b!(c)"),
+            ("::m![u8]", "::m![u8]"),
+            ("m! {}", "m!{ }"),
+        ] {
+            let stream: TokenStream = source.parse().unwrap();
+            let ty = parse_ty(&mut KTokenStream::new(&stream), &tracer)
+                .unwrap()
+                .unwrap();
+            assert!(matches!(ty.kind, TyKind::MacroInvocation { .. }));
+            assert_eq!(ty.to_string(), expected);
+        }
+
+        let stream: TokenStream = "a::b! c".parse().unwrap();
+        assert!(parse_ty(&mut KTokenStream::new(&stream), &tracer).is_err());
+    }
 }